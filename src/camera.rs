@@ -1,12 +1,36 @@
+use std::collections::HashMap;
 use std::io::{self, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use rand;
+pub mod aov;
+pub mod aperture;
+pub mod exposure;
+pub mod projection;
 
 use crate::{
-    image::buffer::ImageBuffer,
+    camera::{
+        aov::{Aov, AovPixel},
+        aperture::Aperture,
+        exposure::Exposure,
+        projection::Projection,
+    },
+    image::{
+        buffer::ImageBuffer,
+        film::Film,
+        filter::{Filter, box_filter::BoxFilter},
+        layers::Layer,
+        tonemap::OutputTransform,
+    },
+    integrator::Integrator,
     math::{interval::Interval, utils::degree_to_radians, vec3::Vec3},
     ray::Ray,
-    scene::hittable::Hittable,
+    sampler::{Sampler, SamplerType},
+    scene::{hittable::HitRecord, world::World},
 };
 
 pub struct Camera {
@@ -17,9 +41,19 @@ pub struct Camera {
     pixel00_loc: Vec3,
     pixel_delta_u: Vec3,
     pixel_delta_v: Vec3,
-    pub max_depth: i32,
+    /// Samples per pixel, or the most a pixel may take when sampling adaptively.
     pub samples_per_pixel: i32,
-    pixel_samples_scale: f64,
+    /// Relative standard error of a pixel's luminance below which it stops
+    /// taking samples; 0 gives every pixel `samples_per_pixel` samples.
+    pub adaptive_threshold: f64,
+    /// Samples every pixel takes before its error is first checked.
+    pub min_samples_per_pixel: i32,
+    sample_counts: ImageBuffer,
+    /// Extra buffers `render` fills from the first hit of every camera ray.
+    pub aovs: Vec<Aov>,
+    aov_layers: Vec<Layer>,
+    /// Mapping from image positions to rays.
+    pub projection: Projection,
     pub vfov: f64,
     pub lookfrom: Vec3,
     pub lookat: Vec3,
@@ -28,9 +62,76 @@ pub struct Camera {
     u: Vec3,
     w: Vec3,
     pub defocus_angle: f64,
+    /// Shape of the lens opening used for defocus blur.
+    pub aperture: Aperture,
     pub focus_dist: f64,
     defocus_disk_u: Vec3,
     defocus_disk_v: Vec3,
+    /// Worker threads used by `render`; 0 uses every available core.
+    pub threads: usize,
+    /// Edge length in pixels of the square tiles handed to each thread.
+    pub tile_size: usize,
+    /// Scene time at which the shutter opens; ray times fall between this
+    /// and `shutter_close`. Moving spheres are only bounded for times in
+    /// [0, 1], so the interval should lie within it.
+    pub shutter_open: f64,
+    /// Scene time at which the shutter closes.
+    pub shutter_close: f64,
+    /// Time for a rolling shutter to sweep from the top row to the bottom
+    /// one, at most the shutter interval. Each row is exposed for the
+    /// interval minus the sweep, the top row starting at `shutter_open` and
+    /// the bottom row ending at `shutter_close`. 0 exposes every row for
+    /// the whole interval.
+    pub rolling_shutter: f64,
+    /// Physical exposure scaling radiance as it is sampled, so it reaches
+    /// every output format; `None` leaves radiance unscaled.
+    pub exposure: Option<Exposure>,
+    /// Display transform given to rendered images for 8-bit output. Its
+    /// `exposure_stops` apply on top of `exposure`.
+    pub output: OutputTransform,
+    /// Reconstruction filter splatting each sample into nearby pixels.
+    pub filter: Arc<dyn Filter>,
+    /// Samples per pixel added by each pass of `render_progressive`.
+    pub samples_per_pass: i32,
+    /// Wall-clock time after which `render_progressive` stops starting new
+    /// passes; `None` renders all `samples_per_pixel` samples.
+    pub time_budget: Option<Duration>,
+    /// File `render_progressive` overwrites with the image after each pass,
+    /// in the format its extension names.
+    pub snapshot_path: Option<PathBuf>,
+    /// Seeds every random decision of a render; equal seeds give equal images.
+    pub seed: u64,
+    /// Sample generator used for pixel jitter, lens, time and scattering.
+    pub sampler: SamplerType,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Tile {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+/// What rendering one tile produced.
+struct TileOutput {
+    /// The tile's samples, splatted into a film that also covers the
+    /// neighbouring pixels the filter reaches.
+    film: Film,
+    /// Samples each pixel took, row by row.
+    taken: Vec<usize>,
+    /// AOV values of each pixel, row by row; empty without AOVs.
+    aovs: Vec<AovPixel>,
+}
+
+/// The work of one sweep over the image.
+struct Pass<'a> {
+    world: &'a World,
+    integrator: &'a dyn Integrator,
+    /// Sample indices to take in every pixel.
+    samples: Range<usize>,
+    adaptive: bool,
+    aovs: &'a [Aov],
 }
 
 impl Camera {
@@ -40,7 +141,6 @@ impl Camera {
 
     pub fn initialize(&mut self) {
         self.center = self.lookfrom;
-        self.pixel_samples_scale = 1.0 / self.samples_per_pixel as f64;
         self.image_height = (self.image_width as f64 / self.aspect_ratio) as usize;
         self.image_height = if self.image_height < 1 {
             1
@@ -73,68 +173,472 @@ impl Camera {
         self.defocus_disk_v = self.v * defocus_radius;
     }
 
-    pub fn render(&mut self, world: &dyn Hittable) -> ImageBuffer {
+    /// Renders `world`, asking `integrator` for the radiance along every
+    /// camera ray. The `aovs` are rendered in the same pass and can be
+    /// fetched afterwards with `aov_layers`.
+    pub fn render(&mut self, world: &World, integrator: &dyn Integrator) -> ImageBuffer {
+        self.initialize();
+        let mut film = Film::new(self.image_width, self.image_height);
+        let mut counts = ImageBuffer::new(self.image_width, self.image_height);
+        let mut aovs = vec![];
+        if !self.aovs.is_empty() {
+            aovs = vec![AovPixel::default(); self.image_width * self.image_height];
+        }
+
+        let pass = Pass {
+            world,
+            integrator,
+            samples: 0..self.samples_per_pixel.max(0) as usize,
+            adaptive: self.adaptive_threshold > 0.0,
+            aovs: &self.aovs,
+        };
+        self.render_samples(&pass, |tile, output| {
+            film.merge(&output.film);
+            for (k, taken) in output.taken.into_iter().enumerate() {
+                let taken = taken as f64;
+                counts.set_pixel(
+                    tile.x + k % tile.width,
+                    tile.y + k / tile.width,
+                    Vec3::new(taken, taken, taken),
+                );
+            }
+            for (k, pixel) in output.aovs.into_iter().enumerate() {
+                let (x, y) = (tile.x + k % tile.width, tile.y + k / tile.width);
+                aovs[y * self.image_width + x] = pixel;
+            }
+        });
+
+        self.aov_layers = self.resolve_aovs(&aovs, &counts);
+        self.sample_counts = counts;
+        self.image(&film)
+    }
+
+    /// Renders passes of `samples_per_pass` samples over the whole image,
+    /// accumulating them in one film, until every pixel has
+    /// `samples_per_pixel` samples or `time_budget` has run out. The budget
+    /// is checked between passes, so the last pass may overrun it. After
+    /// each pass the image so far is written to `snapshot_path`, if set.
+    /// Adaptive sampling and AOVs are not used.
+    pub fn render_progressive(
+        &mut self,
+        world: &World,
+        integrator: &dyn Integrator,
+    ) -> ImageBuffer {
         self.initialize();
-        let mut img = ImageBuffer::new(self.image_width, self.image_height);
-        for j in 0..self.image_height {
-            print!("\rScanlines remaining: {} ", self.image_height - j);
+        let start = Instant::now();
+        let mut film = Film::new(self.image_width, self.image_height);
+
+        let target = self.samples_per_pixel.max(1) as usize;
+        let per_pass = self.samples_per_pass.max(1) as usize;
+        let mut taken = 0;
+        let mut img = self.image(&film);
+        while taken < target {
+            let end = (taken + per_pass).min(target);
+            let pass = Pass {
+                world,
+                integrator,
+                samples: taken..end,
+                adaptive: false,
+                aovs: &[],
+            };
+            self.render_samples(&pass, |_, output| film.merge(&output.film));
+            taken = end;
+
+            img = self.image(&film);
+            if let Some(path) = &self.snapshot_path
+                && let Err(err) = img.save(path)
+            {
+                eprintln!("\nfailed to write snapshot {}: {}", path.display(), err);
+            }
+            let elapsed = start.elapsed();
+            print!(
+                "\rPass complete: {} samples per pixel in {:.1}s ",
+                taken,
+                elapsed.as_secs_f64()
+            );
             io::stdout().flush().unwrap();
-            for i in 0..self.image_width {
-                let mut pixel_color = Vec3::default();
-                for _ in 0..self.samples_per_pixel {
-                    let r = self.get_ray(i as f64, j as f64);
-                    pixel_color = pixel_color + self.color(&r, world, self.max_depth);
-                }
-                img.set_pixel(i, j, pixel_color * self.pixel_samples_scale);
+            if self.time_budget.is_some_and(|budget| elapsed >= budget) {
+                break;
             }
         }
 
+        let taken = taken as f64;
+        self.sample_counts = ImageBuffer::new(self.image_width, self.image_height);
+        self.sample_counts
+            .pixels
+            .fill(Vec3::new(taken, taken, taken));
+        self.aov_layers.clear();
         img
     }
 
-    fn get_ray(&self, i: f64, j: f64) -> Ray {
-        let offset = self.sample_square();
-        let pixel_sample = self.pixel00_loc
-            + ((i + offset.x) * self.pixel_delta_u)
-            + ((j + offset.y) * self.pixel_delta_v);
-        let ray_origin = if self.defocus_angle <= 0.0 {
-            self.center
+    /// The image resolved from `film`, tagged with the display transform.
+    fn image(&self, film: &Film) -> ImageBuffer {
+        let mut img = film.image();
+        img.output = self.output;
+        img
+    }
+
+    /// Samples each pixel took in the last render, in all three channels.
+    pub fn sample_counts(&self) -> &ImageBuffer {
+        &self.sample_counts
+    }
+
+    /// The `aovs` of the last `render`, as layers named after them.
+    pub fn aov_layers(&self) -> &[Layer] {
+        &self.aov_layers
+    }
+
+    /// The image of `aov` from the last `render`, if it was among `aovs`.
+    pub fn aov_layer(&self, aov: Aov) -> Option<&ImageBuffer> {
+        self.aov_layers
+            .iter()
+            .find(|layer| layer.name == aov.name())
+            .map(|layer| &layer.image)
+    }
+
+    /// Turns the per-pixel AOV sums into one layer per AOV, averaging over
+    /// the samples each pixel took and numbering the materials.
+    fn resolve_aovs(&self, pixels: &[AovPixel], counts: &ImageBuffer) -> Vec<Layer> {
+        let mut material_ids = HashMap::new();
+        let materials: Vec<f64> = pixels
+            .iter()
+            .map(|pixel| {
+                if pixel.material == 0 {
+                    return 0.0;
+                }
+                let next = material_ids.len() + 1;
+                *material_ids.entry(pixel.material).or_insert(next) as f64
+            })
+            .collect();
+
+        let mut layers = vec![];
+        for (k, &aov) in self.aovs.iter().enumerate() {
+            let mut image = ImageBuffer::new(self.image_width, self.image_height);
+            for (n, out) in image.pixels.iter_mut().enumerate() {
+                *out = match aov {
+                    Aov::MaterialId => Vec3::new(materials[n], materials[n], materials[n]),
+                    _ if aov.is_averaged() => pixels[n].values[k] / counts.pixels[n].x.max(1.0),
+                    _ => pixels[n].values[k],
+                };
+            }
+            layers.push(Layer {
+                name: aov.name().to_string(),
+                channels: aov.channels(),
+                image,
+            });
+        }
+        layers
+    }
+
+    /// Renders `pass` in parallel tiles, handing each finished tile and
+    /// what it produced to `on_tile`. Tiles are handed over in row-major
+    /// order whatever order they finish in: filters wider than a pixel make
+    /// neighbouring tiles' films overlap, and floating-point sums of the
+    /// overlap depend on the order they are added in.
+    fn render_samples(&self, pass: &Pass, mut on_tile: impl FnMut(Tile, TileOutput)) {
+        let tiles = self.tiles();
+        let next_tile = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        let tiles_ref = &tiles;
+        let next_tile = &next_tile;
+        thread::scope(|scope| {
+            for _ in 0..self.thread_count() {
+                let sender = sender.clone();
+                scope.spawn(move || {
+                    loop {
+                        let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                        let Some(&tile) = tiles_ref.get(index) else {
+                            break;
+                        };
+                        let output = self.render_tile(tile, pass);
+                        if sender.send((index, output)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            let mut finished: Vec<Option<TileOutput>> = tiles_ref.iter().map(|_| None).collect();
+            let mut next = 0;
+            for (done, (index, output)) in receiver.iter().enumerate() {
+                print!("\rTiles remaining: {} ", tiles_ref.len() - done - 1);
+                io::stdout().flush().unwrap();
+                finished[index] = Some(output);
+                while let Some(output) = finished.get_mut(next).and_then(Option::take) {
+                    on_tile(tiles_ref[next], output);
+                    next += 1;
+                }
+            }
+        });
+    }
+
+    /// Number of worker threads to render with; `threads == 0` means one per core.
+    fn thread_count(&self) -> usize {
+        if self.threads > 0 {
+            self.threads
         } else {
-            self.defocus_disk_sample()
+            thread::available_parallelism().map_or(1, |n| n.get())
+        }
+    }
+
+    /// Splits the image into row-major tiles of at most `tile_size` pixels square.
+    fn tiles(&self) -> Vec<Tile> {
+        let size = self.tile_size.max(1);
+        let mut tiles = vec![];
+        for y in (0..self.image_height).step_by(size) {
+            for x in (0..self.image_width).step_by(size) {
+                tiles.push(Tile {
+                    x,
+                    y,
+                    width: size.min(self.image_width - x),
+                    height: size.min(self.image_height - y),
+                });
+            }
+        }
+        tiles
+    }
+
+    /// Renders the pixels of one tile in row-major order.
+    fn render_tile(&self, tile: Tile, pass: &Pass) -> TileOutput {
+        let margin = self.filter.radius().ceil().max(0.0) as usize;
+        let x0 = tile.x.saturating_sub(margin);
+        let y0 = tile.y.saturating_sub(margin);
+        let x1 = (tile.x + tile.width + margin).min(self.image_width);
+        let y1 = (tile.y + tile.height + margin).min(self.image_height);
+        let mut film = Film::window(x0, y0, x1 - x0, y1 - y0);
+
+        let mut sampler = self
+            .sampler
+            .build(self.samples_per_pixel.max(1) as usize, self.seed);
+        let mut taken = Vec::with_capacity(tile.width * tile.height);
+        let mut aovs = vec![];
+        for j in tile.y..tile.y + tile.height {
+            for i in tile.x..tile.x + tile.width {
+                let mut aov = AovPixel {
+                    values: vec![Vec3::default(); pass.aovs.len()],
+                    material: 0,
+                };
+                taken.push(self.render_pixel(i, j, pass, sampler.as_mut(), &mut film, &mut aov));
+                if !pass.aovs.is_empty() {
+                    aovs.push(aov);
+                }
+            }
+        }
+        TileOutput { film, taken, aovs }
+    }
+
+    /// Splats the samples `pass.samples` of pixel (`i`, `j`) into `film` and
+    /// returns how many were taken. Samples are keyed on `seed`, the pixel
+    /// and the sample index, so a pixel's value never depends on which
+    /// thread rendered it. When `pass.adaptive`, sampling stops early once
+    /// the running estimate of the error in the mean luminance is below
+    /// `adaptive_threshold`. The first hits of the camera rays go into
+    /// `aov`, apart from the IDs, which come from the pixel's centre.
+    fn render_pixel(
+        &self,
+        i: usize,
+        j: usize,
+        pass: &Pass,
+        sampler: &mut dyn Sampler,
+        film: &mut Film,
+        aov: &mut AovPixel,
+    ) -> usize {
+        let max_samples = pass.samples.len();
+        let min_samples = (self.min_samples_per_pixel.max(2) as usize).min(max_samples);
+
+        // Welford's running mean and sum of squared deviations of luminance
+        let mut mean = 0.0;
+        let mut m2 = 0.0;
+        let mut taken = 0;
+        let exposure = self.exposure.map_or(1.0, |e| e.scale());
+        if !pass.aovs.is_empty() {
+            self.set_id_aovs(i, j, pass, aov);
+        }
+        while taken < max_samples {
+            sampler.start_pixel_sample(i, j, pass.samples.start + taken);
+            let offset = self.sample_square(sampler.get_2d());
+            let (x, y) = (i as f64 + offset.x, j as f64 + offset.y);
+            let color = match self.get_ray(x, y, sampler) {
+                Some(r) => {
+                    if !pass.aovs.is_empty() {
+                        self.add_aovs(&r, y, pass, aov);
+                    }
+                    pass.integrator.radiance(&r, pass.world, sampler)
+                }
+                None => Vec3::default(),
+            };
+            film.splat(x, y, color * exposure, self.filter.as_ref());
+            taken += 1;
+
+            if !pass.adaptive {
+                continue;
+            }
+            let luminance = color.luminance();
+            let delta = luminance - mean;
+            mean += delta / taken as f64;
+            m2 += delta * (luminance - mean);
+            if taken >= min_samples {
+                let variance = m2 / (taken - 1) as f64;
+                let error = (variance / taken as f64).sqrt() / mean.abs().max(1e-3);
+                if error < self.adaptive_threshold {
+                    break;
+                }
+            }
+        }
+        taken
+    }
+
+    /// The ray through image position (`x`, `y`), where pixel `i`'s centre
+    /// is at `i`, or `None` outside the projection's coverage. Consumes the
+    /// lens and time dimensions, in that order; the caller draws the pixel
+    /// offset first.
+    fn get_ray(&self, x: f64, y: f64, sampler: &mut dyn Sampler) -> Option<Ray> {
+        let lens = sampler.get_2d();
+        let (row_open, row_close) = self.row_exposure(y);
+        let time = row_open + sampler.get_1d() * (row_close - row_open);
+        self.ray_at(x, y, Some(lens), time)
+    }
+
+    /// The ray through image position (`x`, `y`) at `time`, leaving the
+    /// lens at the point `lens` picks, or at its centre for `None`.
+    fn ray_at(&self, x: f64, y: f64, lens: Option<(f64, f64)>, time: f64) -> Option<Ray> {
+        let width = self.image_width as f64;
+        let height = self.image_height as f64;
+
+        let s = (x + 0.5) / width;
+        let t = (y + 0.5) / height;
+
+        let (ray_origin, ray_direction) = match self.projection {
+            Projection::Perspective => {
+                let pixel_sample =
+                    self.pixel00_loc + (x * self.pixel_delta_u) + (y * self.pixel_delta_v);
+                let ray_origin = match lens {
+                    Some(lens) if self.defocus_angle > 0.0 => self.defocus_disk_sample(lens),
+                    _ => self.center,
+                };
+                (ray_origin, pixel_sample - ray_origin)
+            }
+            Projection::Orthographic {
+                height: view_height,
+            } => {
+                let view_width = view_height * width / height;
+                let ray_origin = self.center
+                    + ((s - 0.5) * view_width) * self.u
+                    + ((0.5 - t) * view_height) * self.v;
+                (ray_origin, -self.w)
+            }
+            projection => {
+                let d = projection.panoramic_direction(s, t, width / height)?;
+                (self.center, d.x * self.u + d.y * self.v + d.z * self.w)
+            }
         };
-        let ray_direction = pixel_sample - ray_origin;
-        Ray::new_with_time(ray_origin, ray_direction, rand::random_range(0.0..1.0))
+        Some(Ray::new_with_time(ray_origin, ray_direction, time))
     }
 
-    fn sample_square(&self) -> Vec3 {
-        Vec3::new(
-            rand::random_range(0.0..1.0) - 0.5,
-            rand::random_range(0.0..1.0) - 0.5,
-            0.0,
-        )
+    /// Adds the first hit along camera ray `r`, traced through image row
+    /// `y`, to the pixel's averaged AOVs.
+    fn add_aovs(&self, r: &Ray, y: f64, pass: &Pass, pixel: &mut AovPixel) {
+        let Some(rec) = pass.world.hit(r, Interval::new(0.001, f64::INFINITY)) else {
+            return;
+        };
+        for (value, &aov) in pixel.values.iter_mut().zip(pass.aovs) {
+            let sample = match aov {
+                Aov::Depth => {
+                    let depth = rec.t * r.direction.length();
+                    Vec3::new(depth, depth, depth)
+                }
+                Aov::Position => rec.point,
+                Aov::Normal => rec.normal,
+                Aov::Albedo => rec.material.albedo(&rec),
+                Aov::Motion => self.motion(r, y, &rec),
+                Aov::ObjectId | Aov::MaterialId => continue,
+            };
+            *value = *value + sample;
+        }
     }
 
-    fn defocus_disk_sample(&self) -> Vec3 {
-        let p = Vec3::random_in_unit_disk();
-        self.center + p.x * self.defocus_disk_u + p.y * self.defocus_disk_v
+    /// Sets the ID AOVs of pixel (`i`, `j`) from what a ray through its
+    /// centre and the centre of the lens hits halfway through the row's
+    /// exposure, so they don't depend on the sampler.
+    fn set_id_aovs(&self, i: usize, j: usize, pass: &Pass, pixel: &mut AovPixel) {
+        let (x, y) = (i as f64, j as f64);
+        let (row_open, row_close) = self.row_exposure(y);
+        let Some(rec) = self
+            .ray_at(x, y, None, 0.5 * (row_open + row_close))
+            .and_then(|r| pass.world.hit(&r, Interval::new(0.001, f64::INFINITY)))
+        else {
+            return;
+        };
+        pixel.material = Arc::as_ptr(&rec.material) as *const () as usize;
+        for (value, &aov) in pixel.values.iter_mut().zip(pass.aovs) {
+            if aov == Aov::ObjectId {
+                let id = rec.object_id as f64;
+                *value = Vec3::new(id, id, id);
+            }
+        }
     }
 
-    pub fn color(&self, r: &Ray, world: &dyn Hittable, depth: i32) -> Vec3 {
-        if depth <= 0 {
-            return Vec3::default();
+    /// Distance in pixels that the point `rec` found along `r` moves
+    /// across the image while row `y` is exposed.
+    fn motion(&self, r: &Ray, y: f64, rec: &HitRecord) -> Vec3 {
+        let at = |time: f64| self.project(rec.point + rec.velocity * (time - r.time));
+        let (row_open, row_close) = self.row_exposure(y);
+        match (at(row_open), at(row_close)) {
+            (Some(open), Some(close)) => Vec3::new(close.0 - open.0, close.1 - open.1, 0.0),
+            _ => Vec3::default(),
         }
+    }
 
-        if let Some(rec) = world.hit(r, Interval::new(0.001, f64::INFINITY)) {
-            if let Some((attenuation, scattered)) = rec.material.scatter(r, &rec) {
-                return attenuation * self.color(&scattered, world, depth - 1);
+    /// Image position at which `point` appears, in `get_ray`'s
+    /// coordinates and ignoring defocus, or `None` if it is behind the
+    /// camera or the projection is panoramic.
+    fn project(&self, point: Vec3) -> Option<(f64, f64)> {
+        let d = point - self.center;
+        match self.projection {
+            Projection::Perspective => {
+                let depth = -d.dot(self.w);
+                if depth <= 0.0 {
+                    return None;
+                }
+                let on_plane = self.center + d * (self.focus_dist / depth) - self.pixel00_loc;
+                Some((
+                    on_plane.dot(self.pixel_delta_u) / self.pixel_delta_u.length_squared(),
+                    on_plane.dot(self.pixel_delta_v) / self.pixel_delta_v.length_squared(),
+                ))
+            }
+            Projection::Orthographic {
+                height: view_height,
+            } => {
+                let width = self.image_width as f64;
+                let height = self.image_height as f64;
+                let view_width = view_height * width / height;
+                let s = d.dot(self.u) / view_width + 0.5;
+                let t = 0.5 - d.dot(self.v) / view_height;
+                Some((s * width - 0.5, t * height - 0.5))
             }
-            return Vec3::default();
+            _ => None,
         }
+    }
+
+    /// Times at which image row `y` starts and stops being exposed, where
+    /// row `j`'s centre is at `j`.
+    fn row_exposure(&self, y: f64) -> (f64, f64) {
+        let interval = (self.shutter_close - self.shutter_open).max(0.0);
+        let sweep = self.rolling_shutter.clamp(0.0, interval);
+        let row = ((y + 0.5) / self.image_height as f64).clamp(0.0, 1.0);
+        let open = self.shutter_open + sweep * row;
+        (open, open + interval - sweep)
+    }
 
-        // background
-        let unit = r.direction.normalized();
-        let t = 0.5 * (unit.y + 1.0);
-        Vec3::new(1.0, 1.0, 1.0) * (1.0 - t) + Vec3::new(0.5, 0.7, 1.0) * t
+    fn sample_square(&self, u: (f64, f64)) -> Vec3 {
+        Vec3::new(u.0 - 0.5, u.1 - 0.5, 0.0)
+    }
+
+    fn defocus_disk_sample(&self, u: (f64, f64)) -> Vec3 {
+        let (x, y) = self.aperture.sample(u);
+        self.center + x * self.defocus_disk_u + y * self.defocus_disk_v
     }
 }
 
@@ -148,9 +652,13 @@ impl Default for Camera {
             pixel00_loc: Default::default(),
             pixel_delta_u: Default::default(),
             pixel_delta_v: Default::default(),
-            max_depth: 10,
             samples_per_pixel: 10,
-            pixel_samples_scale: Default::default(),
+            adaptive_threshold: 0.0,
+            min_samples_per_pixel: 16,
+            sample_counts: ImageBuffer::new(0, 0),
+            aovs: vec![],
+            aov_layers: vec![],
+            projection: Projection::default(),
             vfov: 90.0,
             lookfrom: Vec3::default(),
             lookat: Vec3::new(0.0, 0.0, -1.0),
@@ -159,9 +667,105 @@ impl Default for Camera {
             u: Default::default(),
             w: Default::default(),
             defocus_angle: 0.0,
+            aperture: Aperture::default(),
             focus_dist: 10.0,
             defocus_disk_u: Default::default(),
             defocus_disk_v: Default::default(),
+            threads: 0,
+            tile_size: 16,
+            shutter_open: 0.0,
+            shutter_close: 1.0,
+            rolling_shutter: 0.0,
+            exposure: None,
+            output: OutputTransform::default(),
+            filter: Arc::new(BoxFilter::new(0.5)),
+            samples_per_pass: 1,
+            time_budget: None,
+            snapshot_path: None,
+            seed: 0,
+            sampler: SamplerType::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::sphere::Sphere;
+    use crate::image::filter::lanczos::LanczosFilter;
+    use crate::integrator::simple_path::SimplePathIntegrator;
+    use crate::scene::hittable_list::HittableList;
+    use crate::scene::material::lambertian::Lambertian;
+
+    fn test_world() -> World {
+        let material = Arc::new(Lambertian::from_color(Vec3::new(0.7, 0.4, 0.2)));
+        let mut objects = HittableList::new();
+        objects.add(Arc::new(Sphere::new(
+            Vec3::new(0.0, 0.0, -2.0),
+            0.8,
+            material.clone(),
+        )));
+        objects.add(Arc::new(Sphere::new(
+            Vec3::new(0.0, -100.8, -2.0),
+            100.0,
+            material,
+        )));
+        World::new(Arc::new(objects))
+    }
+
+    fn test_camera() -> Camera {
+        let mut camera = Camera::new();
+        camera.image_width = 24;
+        camera.aspect_ratio = 1.5;
+        camera.samples_per_pixel = 4;
+        camera.tile_size = 4;
+        camera
+    }
+
+    fn render_with_threads(threads: usize) -> ImageBuffer {
+        let mut camera = test_camera();
+        camera.threads = threads;
+        camera.filter = Arc::new(LanczosFilter::new(3.0, 3.0));
+        camera.render(&test_world(), &SimplePathIntegrator::new(8))
+    }
+
+    #[test]
+    fn output_does_not_depend_on_thread_count() {
+        let single = render_with_threads(1);
+        for _ in 0..5 {
+            let multi = render_with_threads(8);
+            assert!(
+                single
+                    .pixels
+                    .iter()
+                    .zip(&multi.pixels)
+                    .all(|(a, b)| a.x.to_bits() == b.x.to_bits()
+                        && a.y.to_bits() == b.y.to_bits()
+                        && a.z.to_bits() == b.z.to_bits())
+            );
+        }
+    }
+
+    #[test]
+    fn object_ids_do_not_depend_on_the_sampler() {
+        let world = test_world();
+        let ids = |sampler: SamplerType| {
+            let mut camera = test_camera();
+            camera.sampler = sampler;
+            camera.aovs = vec![Aov::ObjectId];
+            camera.render(&world, &SimplePathIntegrator::new(1));
+            camera.aov_layer(Aov::ObjectId).unwrap().pixels.clone()
+        };
+
+        let independent = ids(SamplerType::Independent);
+        assert!(independent.iter().any(|id| id.x == 1.0));
+        assert!(independent.iter().any(|id| id.x == 2.0));
+        for sampler in [
+            SamplerType::Stratified,
+            SamplerType::Halton,
+            SamplerType::Sobol,
+        ] {
+            assert!(ids(sampler) == independent);
         }
     }
 }
@@ -0,0 +1,85 @@
+use crate::math::vec3::Vec3;
+
+/// Arbitrary output variable: a buffer of data about what each pixel's
+/// camera rays first hit, rendered alongside the image. Pixels whose rays
+/// escape the scene get zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aov {
+    /// Distance from the ray origin to the hit point.
+    Depth,
+    /// World-space hit point.
+    Position,
+    /// Shading normal, facing the camera.
+    Normal,
+    /// Reflectance of the hit material.
+    Albedo,
+    /// Position of the hit object in the scene's top-level list, from 1.
+    ObjectId,
+    /// Materials numbered from 1 in the order they first appear scanning
+    /// the image row by row.
+    MaterialId,
+    /// Movement of the hit point across the image, in pixels, while the
+    /// row it was seen from is exposed. With a rolling shutter that is
+    /// shorter than the whole shutter interval. Zero for the panoramic
+    /// projections.
+    Motion,
+}
+
+impl Aov {
+    pub const ALL: [Aov; 7] = [
+        Aov::Depth,
+        Aov::Position,
+        Aov::Normal,
+        Aov::Albedo,
+        Aov::ObjectId,
+        Aov::MaterialId,
+        Aov::Motion,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|aov| aov.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Position => "position",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::ObjectId => "object_id",
+            Aov::MaterialId => "material_id",
+            Aov::Motion => "motion",
+        }
+    }
+
+    /// Names of the channels the AOV stores in a pixel's x, y and z.
+    /// Single-channel AOVs repeat their value in all three.
+    pub fn channels(self) -> &'static [&'static str] {
+        match self {
+            Aov::Depth => &["Z"],
+            Aov::Position | Aov::Normal => &["X", "Y", "Z"],
+            Aov::Albedo => &["R", "G", "B"],
+            Aov::ObjectId | Aov::MaterialId => &["id"],
+            Aov::Motion => &["X", "Y"],
+        }
+    }
+
+    /// Whether the AOV is the mean over a pixel's samples. IDs are not,
+    /// since a blend of two IDs names neither; they come from a ray through
+    /// the pixel's centre, so pixels on an edge between objects get the
+    /// same ID whatever the sampler.
+    pub(crate) fn is_averaged(self) -> bool {
+        !matches!(self, Aov::ObjectId | Aov::MaterialId)
+    }
+}
+
+/// AOV values of one pixel, in the order of `Camera::aovs`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AovPixel {
+    /// Sums over the samples for averaged AOVs, values at the pixel's
+    /// centre for the rest.
+    pub(crate) values: Vec<Vec3>,
+    /// Address of the material the ray through the pixel's centre hit, or
+    /// 0 if it missed.
+    pub(crate) material: usize,
+}
@@ -0,0 +1,138 @@
+use std::f64::consts::PI;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::sampler::sample_uniform_disk;
+
+/// Shape of the lens opening, which gives out-of-focus highlights (bokeh)
+/// their shape. The shape is scaled to the defocus radius.
+#[derive(Clone, Default)]
+pub enum Aperture {
+    /// A round opening.
+    #[default]
+    Disk,
+    /// A regular polygon with `sides` corners on the unit circle, turned by
+    /// `rotation` degrees, like a lens with straight diaphragm blades.
+    Polygon { sides: u32, rotation: f64 },
+    /// An opening traced from a greyscale mask; brighter pixels let more
+    /// light through.
+    Image(Arc<ApertureImage>),
+}
+
+impl Aperture {
+    /// A point on the opening, within [-1, 1]^2, distributed in proportion
+    /// to how much light passes there.
+    pub fn sample(&self, u: (f64, f64)) -> (f64, f64) {
+        match self {
+            Aperture::Disk => {
+                let p = sample_uniform_disk(u);
+                (p.x, p.y)
+            }
+            Aperture::Polygon { sides, rotation } => {
+                sample_polygon((*sides).max(3), rotation.to_radians(), u)
+            }
+            Aperture::Image(image) => image.sample(u),
+        }
+    }
+}
+
+/// Uniform over a regular polygon inscribed in the unit circle: picks one of
+/// the triangles fanning out from the centre, then a point inside it.
+fn sample_polygon(sides: u32, rotation: f64, u: (f64, f64)) -> (f64, f64) {
+    let scaled = u.0 * sides as f64;
+    let side = (scaled as u32).min(sides - 1);
+    let u0 = scaled - side as f64;
+
+    let angle = |k: u32| rotation + 2.0 * PI * k as f64 / sides as f64;
+    let (a, b) = (angle(side), angle(side + 1));
+
+    // uniform in the triangle (centre, corner a, corner b)
+    let r = u0.sqrt();
+    let (wa, wb) = (r * (1.0 - u.1), r * u.1);
+    (wa * a.cos() + wb * b.cos(), wa * a.sin() + wb * b.sin())
+}
+
+/// A greyscale aperture mask with tables for sampling it in proportion to
+/// brightness.
+pub struct ApertureImage {
+    width: usize,
+    height: usize,
+    /// Cumulative brightness of the rows, normalised to end at 1.
+    row_cdf: Vec<f64>,
+    /// Cumulative brightness within each row, normalised to end at 1.
+    column_cdfs: Vec<Vec<f64>>,
+}
+
+impl ApertureImage {
+    /// Loads a mask, stretched over the square aperture. A mask with no
+    /// bright pixels lets light through everywhere.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_luma32f();
+        let (w, h) = image.dimensions();
+        let (width, height) = (w as usize, h as usize);
+        let values: Vec<f64> = image.pixels().map(|p| p[0].max(0.0) as f64).collect();
+        let total: f64 = values.iter().sum();
+        let values = if total > 0.0 {
+            values
+        } else {
+            vec![1.0; width * height]
+        };
+
+        let mut row_sums = Vec::with_capacity(height);
+        let mut column_cdfs = Vec::with_capacity(height);
+        for row in values.chunks(width.max(1)) {
+            let (cdf, sum) = cumulative(row);
+            row_sums.push(sum);
+            column_cdfs.push(cdf);
+        }
+        let (row_cdf, _) = cumulative(&row_sums);
+
+        Ok(Self {
+            width,
+            height,
+            row_cdf,
+            column_cdfs,
+        })
+    }
+
+    fn sample(&self, u: (f64, f64)) -> (f64, f64) {
+        if self.width == 0 || self.height == 0 {
+            return (0.0, 0.0);
+        }
+        let (row, v) = invert_cdf(&self.row_cdf, u.1);
+        let (column, s) = invert_cdf(&self.column_cdfs[row], u.0);
+        let x = (column as f64 + s) / self.width as f64;
+        let y = (row as f64 + v) / self.height as f64;
+        (2.0 * x - 1.0, 1.0 - 2.0 * y)
+    }
+}
+
+/// Normalised running sums of `values`, with their total. Starts at 0, so
+/// the result is one longer than `values`. All zeros become uniform.
+fn cumulative(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut cdf = Vec::with_capacity(values.len() + 1);
+    let mut sum = 0.0;
+    cdf.push(0.0);
+    for &value in values {
+        sum += value;
+        cdf.push(sum);
+    }
+    let n = values.len() as f64;
+    for (i, c) in cdf.iter_mut().enumerate() {
+        *c = if sum > 0.0 { *c / sum } else { i as f64 / n };
+    }
+    (cdf, sum)
+}
+
+/// The bin of `cdf` that `u` falls in and `u`'s position within it.
+fn invert_cdf(cdf: &[f64], u: f64) -> (usize, f64) {
+    let bins = cdf.len() - 1;
+    let index = cdf.partition_point(|&c| c <= u).clamp(1, bins) - 1;
+    let width = cdf[index + 1] - cdf[index];
+    let offset = if width > 0.0 {
+        ((u - cdf[index]) / width).clamp(0.0, 1.0)
+    } else {
+        0.5
+    };
+    (index, offset)
+}
@@ -0,0 +1,38 @@
+/// Photographic exposure settings. Together they scale scene radiance to
+/// the values written to the image, so scenes can be lit in physical units
+/// and "shot" like a real camera.
+///
+/// These only affect brightness: depth of field still comes from the
+/// camera's `defocus_angle` and motion blur from its shutter interval.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exposure {
+    /// Aperture f-number, e.g. 2.8 or 16.
+    pub f_number: f64,
+    /// Shutter speed in seconds, e.g. 1/125.
+    pub shutter_speed: f64,
+    /// Sensor sensitivity, e.g. 100 or 800.
+    pub iso: f64,
+}
+
+impl Exposure {
+    pub fn new(f_number: f64, shutter_speed: f64, iso: f64) -> Self {
+        Self {
+            f_number,
+            shutter_speed,
+            iso,
+        }
+    }
+
+    /// Exposure value at ISO 100: `log2(N^2 / t * 100 / S)`.
+    pub fn ev100(&self) -> f64 {
+        (self.f_number * self.f_number / self.shutter_speed * 100.0 / self.iso).log2()
+    }
+
+    /// Factor applied to radiance, from the saturation-based sensitivity
+    /// model: the luminance that just saturates the sensor maps to 1.
+    /// `1.2 = 78 / (0.65 * 100)` combines the lens transmittance and the
+    /// ISO 12232 saturation constant.
+    pub fn scale(&self) -> f64 {
+        1.0 / (1.2 * self.ev100().exp2())
+    }
+}
@@ -0,0 +1,103 @@
+use std::f64::consts::PI;
+
+use crate::math::{utils::degree_to_radians, vec3::Vec3};
+
+/// How image positions map to camera rays. Every projection is framed by
+/// the camera's `lookfrom`, `lookat` and `vup`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Projection {
+    /// Thin-lens perspective using `vfov`, `focus_dist` and `defocus_angle`.
+    #[default]
+    Perspective,
+    /// Parallel rays along the view direction from a `height` world units
+    /// tall rectangle centred on `lookfrom`, as for elevations and plans.
+    Orthographic { height: f64 },
+    /// Equidistant fisheye: the angle from the view direction grows linearly
+    /// with distance from the image centre, reaching `fov / 2` degrees on
+    /// the largest circle that fits the image. Pixels outside it are black.
+    Fisheye { fov: f64 },
+    /// Full 360° by 180° latitude-longitude panorama centred on the view
+    /// direction; usually rendered at a 2:1 aspect ratio.
+    Equirectangular,
+    /// Six 90° faces side by side, each square: +x, -x, +y, -y, +z, -z of
+    /// the camera frame (x right, y up, z backwards, so -z faces `lookat`).
+    /// Usually rendered at a 6:1 aspect ratio.
+    Cubemap,
+}
+
+impl Projection {
+    /// Direction in the camera frame (x right, y up, z backwards) for
+    /// panoramic projections, at position (`s`, `t`) in [0, 1]^2 across and
+    /// down an image `aspect` times as wide as it is tall. `None` for points
+    /// the projection doesn't cover, and for the planar projections.
+    pub(crate) fn panoramic_direction(self, s: f64, t: f64, aspect: f64) -> Option<Vec3> {
+        match self {
+            Projection::Perspective | Projection::Orthographic { .. } => None,
+            Projection::Fisheye { fov } => {
+                // offsets in units of the image height
+                let x = (s - 0.5) * aspect;
+                let y = 0.5 - t;
+                let r = (x * x + y * y).sqrt() / (0.5 * aspect.min(1.0));
+                if r > 1.0 {
+                    return None;
+                }
+                let theta = r * degree_to_radians(fov) / 2.0;
+                let phi = y.atan2(x);
+                Some(Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    -theta.cos(),
+                ))
+            }
+            Projection::Equirectangular => {
+                let longitude = (s - 0.5) * 2.0 * PI;
+                let latitude = (0.5 - t) * PI;
+                Some(Vec3::new(
+                    latitude.cos() * longitude.sin(),
+                    latitude.sin(),
+                    -latitude.cos() * longitude.cos(),
+                ))
+            }
+            Projection::Cubemap => {
+                let face_position = s * 6.0;
+                let face = (face_position as usize).min(5);
+                let a = 2.0 * (face_position - face as f64) - 1.0;
+                let b = 1.0 - 2.0 * t;
+                // forward, right and up of each face, with right = forward x up
+                let (forward, right, up) = match face {
+                    0 => (
+                        Vec3::new(1.0, 0.0, 0.0),
+                        Vec3::new(0.0, 0.0, 1.0),
+                        Vec3::new(0.0, 1.0, 0.0),
+                    ),
+                    1 => (
+                        Vec3::new(-1.0, 0.0, 0.0),
+                        Vec3::new(0.0, 0.0, -1.0),
+                        Vec3::new(0.0, 1.0, 0.0),
+                    ),
+                    2 => (
+                        Vec3::new(0.0, 1.0, 0.0),
+                        Vec3::new(1.0, 0.0, 0.0),
+                        Vec3::new(0.0, 0.0, 1.0),
+                    ),
+                    3 => (
+                        Vec3::new(0.0, -1.0, 0.0),
+                        Vec3::new(1.0, 0.0, 0.0),
+                        Vec3::new(0.0, 0.0, -1.0),
+                    ),
+                    4 => (
+                        Vec3::new(0.0, 0.0, 1.0),
+                        Vec3::new(-1.0, 0.0, 0.0),
+                        Vec3::new(0.0, 1.0, 0.0),
+                    ),
+                    _ => (
+                        Vec3::new(0.0, 0.0, -1.0),
+                        Vec3::new(1.0, 0.0, 0.0),
+                        Vec3::new(0.0, 1.0, 0.0),
+                    ),
+                };
+                Some((forward + a * right + b * up).normalized())
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use crate::math::interval::Interval;
+use crate::math::random::{Rng, mix};
+use crate::math::vec3::Vec3;
+use crate::ray::Ray;
+use crate::scene::aabb::Aabb;
+use crate::scene::hittable::{HitRecord, Hittable};
+use crate::scene::material::Material;
+use crate::scene::material::isotropic::Isotropic;
+use crate::scene::texture::Texture;
+
+/// A volume of uniform density filling a closed (convex) boundary.
+pub struct ConstantMedium {
+    pub boundary: Arc<dyn Hittable>,
+    neg_inv_density: f64,
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Arc<dyn Hittable>, density: f64, tex: Arc<dyn Texture>) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function: Arc::new(Isotropic::from_texture(tex)),
+        }
+    }
+
+    pub fn from_color(boundary: Arc<dyn Hittable>, density: f64, albedo: Vec3) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function: Arc::new(Isotropic::from_color(albedo)),
+        }
+    }
+}
+
+impl ConstantMedium {
+    /// `hit` has no sampler, so the free-flight distance is drawn from a
+    /// generator keyed on the ray itself. Renders stay reproducible, and a
+    /// ray tested more than once sees the same medium interaction.
+    fn ray_rng(ray: &Ray) -> Rng {
+        let key = [
+            ray.origin.x,
+            ray.origin.y,
+            ray.origin.z,
+            ray.direction.x,
+            ray.direction.y,
+            ray.direction.z,
+            ray.time,
+        ]
+        .iter()
+        .fold(0, |hash, value| mix(hash ^ value.to_bits()));
+        Rng::new(key)
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        // find where the ray enters and leaves the boundary, wherever the origin is
+        let mut enter = self.boundary.hit(ray, Interval::universe())?;
+        let mut exit = self
+            .boundary
+            .hit(ray, Interval::new(enter.t + 0.0001, f64::INFINITY))?;
+
+        enter.t = enter.t.max(ray_t.min);
+        exit.t = exit.t.min(ray_t.max);
+        if enter.t >= exit.t {
+            return None;
+        }
+        enter.t = enter.t.max(0.0);
+
+        let ray_length = ray.direction.length();
+        let distance_inside_boundary = (exit.t - enter.t) * ray_length;
+        let hit_distance = self.neg_inv_density * (1.0 - Self::ray_rng(ray).next_f64()).ln();
+
+        if hit_distance > distance_inside_boundary {
+            return None;
+        }
+
+        let t = enter.t + hit_distance / ray_length;
+        let point = ray.at(t);
+
+        // normal and front_face are arbitrary inside a medium
+        Some(HitRecord {
+            point,
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            t,
+            front_face: true,
+            material: self.phase_function.clone(),
+            u: 0.0,
+            v: 0.0,
+            velocity: Vec3::default(),
+            object_id: 0,
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use crate::geometry::triangle::{DEFAULT_UVS, intersect, surface_hit, triangle_bbox};
+use crate::math::interval::Interval;
+use crate::math::vec3::Vec3;
+use crate::ray::Ray;
+use crate::scene::aabb::Aabb;
+use crate::scene::bvh::BvhNode;
+use crate::scene::hittable::{HitRecord, Hittable};
+use crate::scene::material::Material;
+
+/// Indexed triangle mesh. Vertex attributes are shared between triangles;
+/// `normals` and `uvs` are either empty or have one entry per position.
+pub struct TriangleMesh {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<(f64, f64)>,
+    pub indices: Vec<[usize; 3]>,
+    pub material: Arc<dyn Material>,
+}
+
+impl TriangleMesh {
+    pub fn new(
+        positions: Vec<Vec3>,
+        normals: Vec<Vec3>,
+        uvs: Vec<(f64, f64)>,
+        indices: Vec<[usize; 3]>,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(
+            normals.is_empty() || normals.len() == positions.len(),
+            "mesh needs one normal per vertex"
+        );
+        assert!(
+            uvs.is_empty() || uvs.len() == positions.len(),
+            "mesh needs one uv per vertex"
+        );
+        assert!(
+            indices.iter().flatten().all(|&i| i < positions.len()),
+            "mesh index out of range"
+        );
+        Self {
+            positions,
+            normals,
+            uvs,
+            indices,
+            material,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// One hittable per face, sharing this mesh's vertex data. Use these to
+    /// place the faces directly into a `BvhNode` or `HittableList`.
+    pub fn triangles(mesh: &Arc<Self>) -> Vec<Arc<dyn Hittable>> {
+        (0..mesh.len())
+            .map(|index| {
+                Arc::new(MeshTriangle {
+                    mesh: mesh.clone(),
+                    index,
+                    bbox: triangle_bbox(&mesh.face_positions(index)),
+                }) as Arc<dyn Hittable>
+            })
+            .collect()
+    }
+
+    /// Wraps the whole mesh in its own BVH, or `None` if it has no faces.
+    pub fn into_bvh(self) -> Option<BvhNode> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut triangles = Self::triangles(&Arc::new(self));
+        Some(BvhNode::from_objects(&mut triangles))
+    }
+
+    fn face_positions(&self, index: usize) -> [Vec3; 3] {
+        self.indices[index].map(|i| self.positions[i])
+    }
+
+    fn face_normals(&self, index: usize) -> Option<[Vec3; 3]> {
+        if self.normals.is_empty() {
+            return None;
+        }
+        Some(self.indices[index].map(|i| self.normals[i]))
+    }
+
+    fn face_uvs(&self, index: usize) -> [(f64, f64); 3] {
+        if self.uvs.is_empty() {
+            return DEFAULT_UVS;
+        }
+        self.indices[index].map(|i| self.uvs[i])
+    }
+}
+
+/// A single face of a `TriangleMesh`.
+pub struct MeshTriangle {
+    mesh: Arc<TriangleMesh>,
+    index: usize,
+    bbox: Aabb,
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let p = self.mesh.face_positions(self.index);
+        let (t, b1, b2) = intersect(&p, ray, ray_t)?;
+        Some(surface_hit(
+            &p,
+            self.mesh.face_normals(self.index).as_ref(),
+            &self.mesh.face_uvs(self.index),
+            t,
+            b1,
+            b2,
+            self.mesh.material.clone(),
+            ray,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -1 +1,7 @@
+pub mod constant_medium;
+pub mod mesh;
+pub mod obj;
+pub mod quad;
 pub mod sphere;
+pub mod transform;
+pub mod triangle;
@@ -0,0 +1,561 @@
+//! Wavefront OBJ/MTL import.
+//!
+//! Faces are grouped into one `TriangleMesh` per material. MTL entries are
+//! mapped onto the materials this crate has:
+//! - `d < 1`, `Tr > 0` or a refractive `illum` (4, 6, 7, 9) becomes a
+//!   `Dielectric` with index `Ni`
+//! - `illum 3`, or a specular colour `Ks` brighter than `Kd` without a
+//!   `map_Kd` texture, becomes a `Metal` tinted by `Ks`, with fuzz derived
+//!   from the exponent `Ns`; an `illum 3` texture is ignored with a warning
+//! - everything else is `Lambertian`, textured by `map_Kd` when present
+//!
+//! Faces using a material the MTL files do not define get the default
+//! material, with a warning. Warnings are returned with the meshes for the
+//! caller to report.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::geometry::mesh::TriangleMesh;
+use crate::math::vec3::Vec3;
+use crate::scene::material::Material;
+use crate::scene::material::dielectric::Dielectric;
+use crate::scene::material::lambertian::Lambertian;
+use crate::scene::material::metal::Metal;
+use crate::scene::texture::image_texture::ImageTexture;
+
+#[derive(Debug)]
+pub enum ObjError {
+    /// An OBJ, MTL or texture file could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// A texture referenced by `map_Kd` could not be decoded.
+    Texture {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+    /// A statement in an OBJ or MTL file could not be understood.
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            ObjError::Texture { path, source } => write!(f, "{}: {}", path.display(), source),
+            ObjError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+        }
+    }
+}
+
+/// Something in an OBJ or MTL file that was loaded differently than written.
+#[derive(Debug)]
+pub enum ObjWarning {
+    /// `usemtl` named a material no MTL file defines; its faces get the
+    /// default material.
+    UnknownMaterial {
+        path: PathBuf,
+        line: usize,
+        name: String,
+    },
+    /// A reflective (`illum 3`) material became a `Metal`, which can't
+    /// use its `map_Kd` texture.
+    IgnoredTexture {
+        path: PathBuf,
+        material: String,
+        texture: PathBuf,
+    },
+}
+
+impl fmt::Display for ObjWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjWarning::UnknownMaterial { path, line, name } => write!(
+                f,
+                "{}:{}: unknown material `{}`, using the default",
+                path.display(),
+                line,
+                name
+            ),
+            ObjWarning::IgnoredTexture {
+                path,
+                material,
+                texture,
+            } => write!(
+                f,
+                "{}: map_Kd `{}` is ignored on reflective (illum 3) material `{}`",
+                path.display(),
+                texture.display(),
+                material
+            ),
+        }
+    }
+}
+
+/// The contents of an OBJ file.
+pub struct ObjModel {
+    /// One mesh per material used.
+    pub meshes: Vec<TriangleMesh>,
+    pub warnings: Vec<ObjWarning>,
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjError::Io { source, .. } => Some(source),
+            ObjError::Texture { source, .. } => Some(source),
+            ObjError::Parse { .. } => None,
+        }
+    }
+}
+
+/// Loads every face of an OBJ file, one mesh per material used. Faces with no
+/// `usemtl`, or an unknown one, get `default_material`.
+pub fn load_obj<P: AsRef<Path>>(
+    path: P,
+    default_material: Arc<dyn Material>,
+) -> Result<ObjModel, ObjError> {
+    let path = path.as_ref();
+    let source = read(path)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut positions = vec![];
+    let mut normals = vec![];
+    let mut uvs = vec![];
+    let mut materials: HashMap<String, Arc<dyn Material>> = HashMap::new();
+    let mut warnings = vec![];
+
+    let mut builders: Vec<MeshBuilder> = vec![MeshBuilder::new(default_material)];
+    let mut current = 0;
+    let mut by_name: HashMap<String, usize> = HashMap::new();
+
+    for (number, line) in source.lines().enumerate() {
+        let err = |message: String| ObjError::Parse {
+            path: path.to_path_buf(),
+            line: number + 1,
+            message,
+        };
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => positions.push(parse_vec3(&args).map_err(err)?),
+            "vn" => normals.push(parse_vec3(&args).map_err(err)?),
+            "vt" => {
+                let uv = parse_floats(&args, 1).map_err(err)?;
+                uvs.push((uv[0], uv.get(1).copied().unwrap_or(0.0)));
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(err("face needs at least three vertices".to_string()));
+                }
+                let corners = args
+                    .iter()
+                    .map(|a| parse_corner(a, positions.len(), uvs.len(), normals.len()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(err)?;
+                builders[current].add_polygon(&corners, &positions, &uvs, &normals);
+            }
+            "mtllib" => {
+                for file in args {
+                    load_mtl(&dir.join(file), &mut materials, &mut warnings)?;
+                }
+            }
+            "usemtl" => {
+                let name = args.join(" ");
+                current = match by_name.get(&name) {
+                    Some(&index) => index,
+                    None => {
+                        let index = match materials.get(&name) {
+                            Some(material) => {
+                                builders.push(MeshBuilder::new(material.clone()));
+                                builders.len() - 1
+                            }
+                            None => {
+                                warnings.push(ObjWarning::UnknownMaterial {
+                                    path: path.to_path_buf(),
+                                    line: number + 1,
+                                    name: name.clone(),
+                                });
+                                0
+                            }
+                        };
+                        by_name.insert(name, index);
+                        index
+                    }
+                };
+            }
+            // groups, objects and smoothing groups carry no geometry
+            _ => {}
+        }
+    }
+
+    let meshes = builders
+        .into_iter()
+        .filter(|b| !b.indices.is_empty())
+        .map(MeshBuilder::build)
+        .collect();
+    Ok(ObjModel { meshes, warnings })
+}
+
+/// A face corner: zero-based position, texture coordinate and normal indices.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+struct MeshBuilder {
+    material: Arc<dyn Material>,
+    positions: Vec<Vec3>,
+    normals: Vec<Option<Vec3>>,
+    uvs: Vec<Option<(f64, f64)>>,
+    indices: Vec<[usize; 3]>,
+    vertex_of: HashMap<Corner, usize>,
+}
+
+impl MeshBuilder {
+    fn new(material: Arc<dyn Material>) -> Self {
+        Self {
+            material,
+            positions: vec![],
+            normals: vec![],
+            uvs: vec![],
+            indices: vec![],
+            vertex_of: HashMap::new(),
+        }
+    }
+
+    fn vertex(
+        &mut self,
+        corner: Corner,
+        positions: &[Vec3],
+        uvs: &[(f64, f64)],
+        normals: &[Vec3],
+    ) -> usize {
+        if let Some(&index) = self.vertex_of.get(&corner) {
+            return index;
+        }
+        let (p, t, n) = corner;
+        self.positions.push(positions[p]);
+        self.uvs.push(t.map(|t| uvs[t]));
+        self.normals.push(n.map(|n| normals[n]));
+        let index = self.positions.len() - 1;
+        self.vertex_of.insert(corner, index);
+        index
+    }
+
+    /// Fan-triangulates a convex polygon.
+    fn add_polygon(
+        &mut self,
+        corners: &[Corner],
+        positions: &[Vec3],
+        uvs: &[(f64, f64)],
+        normals: &[Vec3],
+    ) {
+        let first = self.vertex(corners[0], positions, uvs, normals);
+        for pair in corners[1..].windows(2) {
+            let b = self.vertex(pair[0], positions, uvs, normals);
+            let c = self.vertex(pair[1], positions, uvs, normals);
+            self.indices.push([first, b, c]);
+        }
+    }
+
+    /// Per-vertex attributes are only kept when every vertex has them.
+    fn build(self) -> TriangleMesh {
+        let normals = self.normals.into_iter().collect::<Option<Vec<_>>>();
+        let uvs = self.uvs.into_iter().collect::<Option<Vec<_>>>();
+        TriangleMesh::new(
+            self.positions,
+            normals.unwrap_or_default(),
+            uvs.unwrap_or_default(),
+            self.indices,
+            self.material,
+        )
+    }
+}
+
+#[derive(Default)]
+struct MtlEntry {
+    kd: Option<Vec3>,
+    ks: Option<Vec3>,
+    ns: Option<f64>,
+    ni: Option<f64>,
+    dissolve: Option<f64>,
+    illum: Option<u32>,
+    map_kd: Option<PathBuf>,
+}
+
+impl MtlEntry {
+    /// The material for entry `name` of the MTL file at `path`.
+    fn into_material(
+        self,
+        path: &Path,
+        name: &str,
+        warnings: &mut Vec<ObjWarning>,
+    ) -> Result<Arc<dyn Material>, ObjError> {
+        let kd = self.kd.unwrap_or(Vec3::new(0.8, 0.8, 0.8));
+        let ks = self.ks.unwrap_or_default();
+
+        let transparent =
+            self.dissolve.is_some_and(|d| d < 1.0) || matches!(self.illum, Some(4 | 6 | 7 | 9));
+        if transparent {
+            return Ok(Arc::new(Dielectric::new(self.ni.unwrap_or(1.5))));
+        }
+
+        // a texture describes the diffuse colour better than a guess from
+        // Ks, so only an explicit illum 3 overrides it
+        let reflective = self.illum == Some(3);
+        let shiny = reflective || (self.map_kd.is_none() && max_component(ks) > max_component(kd));
+        if shiny {
+            if let Some(map) = self.map_kd {
+                warnings.push(ObjWarning::IgnoredTexture {
+                    path: path.to_path_buf(),
+                    material: name.to_string(),
+                    texture: map,
+                });
+            }
+            // roughness of the Blinn-Phong lobe with this exponent
+            let fuzz = (2.0 / (self.ns.unwrap_or(0.0) + 2.0)).sqrt();
+            return Ok(Arc::new(Metal::new(ks, fuzz)));
+        }
+
+        if let Some(map) = self.map_kd {
+            let texture = ImageTexture::load(&map).map_err(|source| ObjError::Texture {
+                path: map.clone(),
+                source,
+            })?;
+            return Ok(Arc::new(Lambertian::from_texture(Arc::new(texture))));
+        }
+        Ok(Arc::new(Lambertian::from_color(kd)))
+    }
+}
+
+fn load_mtl(
+    path: &Path,
+    materials: &mut HashMap<String, Arc<dyn Material>>,
+    warnings: &mut Vec<ObjWarning>,
+) -> Result<(), ObjError> {
+    let source = read(path)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut current: Option<(String, MtlEntry)> = None;
+    for (number, line) in source.lines().enumerate() {
+        let err = |message: String| ObjError::Parse {
+            path: path.to_path_buf(),
+            line: number + 1,
+            message,
+        };
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if let Some((name, entry)) = current.take() {
+                let material = entry.into_material(path, &name, warnings)?;
+                materials.insert(name, material);
+            }
+            current = Some((args.join(" "), MtlEntry::default()));
+            continue;
+        }
+
+        let Some((_, entry)) = current.as_mut() else {
+            continue;
+        };
+        match keyword {
+            "Kd" => entry.kd = Some(parse_vec3(&args).map_err(err)?),
+            "Ks" => entry.ks = Some(parse_vec3(&args).map_err(err)?),
+            "Ns" => entry.ns = Some(parse_floats(&args, 1).map_err(err)?[0]),
+            "Ni" => entry.ni = Some(parse_floats(&args, 1).map_err(err)?[0]),
+            "d" => entry.dissolve = Some(parse_floats(&args, 1).map_err(err)?[0]),
+            "Tr" => entry.dissolve = Some(1.0 - parse_floats(&args, 1).map_err(err)?[0]),
+            "illum" => {
+                let illum = args.first().and_then(|a| a.parse().ok());
+                entry.illum = Some(illum.ok_or_else(|| err("bad illum value".to_string()))?);
+            }
+            // options such as `-bm 1` may precede the file name
+            "map_Kd" => match args.last() {
+                Some(file) => entry.map_kd = Some(dir.join(file)),
+                None => return Err(err("map_Kd needs a file name".to_string())),
+            },
+            _ => {}
+        }
+    }
+    if let Some((name, entry)) = current {
+        let material = entry.into_material(path, &name, warnings)?;
+        materials.insert(name, material);
+    }
+    Ok(())
+}
+
+fn read(path: &Path) -> Result<String, ObjError> {
+    fs::read_to_string(path).map_err(|source| ObjError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn max_component(v: Vec3) -> f64 {
+    v.x.max(v.y).max(v.z)
+}
+
+fn parse_floats(args: &[&str], min: usize) -> Result<Vec<f64>, String> {
+    if args.len() < min {
+        return Err(format!("expected {} numbers, found {}", min, args.len()));
+    }
+    args.iter()
+        .map(|a| {
+            a.parse::<f64>()
+                .map_err(|_| format!("invalid number `{}`", a))
+        })
+        .collect()
+}
+
+fn parse_vec3(args: &[&str]) -> Result<Vec3, String> {
+    let v = parse_floats(args, 3)?;
+    Ok(Vec3::new(v[0], v[1], v[2]))
+}
+
+/// Parses `v`, `v/vt`, `v//vn` or `v/vt/vn`, resolving negative (relative)
+/// indices against the number of attributes seen so far.
+fn parse_corner(
+    token: &str,
+    positions: usize,
+    uvs: usize,
+    normals: usize,
+) -> Result<Corner, String> {
+    let mut parts = token.split('/');
+    let p = resolve_index(parts.next(), positions, token)?
+        .ok_or_else(|| format!("face corner `{}` has no position", token))?;
+    let t = resolve_index(parts.next(), uvs, token)?;
+    let n = resolve_index(parts.next(), normals, token)?;
+    Ok((p, t, n))
+}
+
+fn resolve_index(part: Option<&str>, count: usize, token: &str) -> Result<Option<usize>, String> {
+    let Some(part) = part.filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    let index: i64 = part
+        .parse()
+        .map_err(|_| format!("invalid index in face corner `{}`", token))?;
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(format!("index out of range in face corner `{}`", token));
+    }
+    Ok(Some(resolved as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::hittable::HitRecord;
+
+    /// A fresh directory holding `files`, named after the test using it.
+    fn write_files(test: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("obj-{}-{}", test, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in files {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+        dir
+    }
+
+    fn grey() -> Arc<dyn Material> {
+        Arc::new(Lambertian::from_color(Vec3::new(0.1, 0.2, 0.3)))
+    }
+
+    fn albedo(mesh: &TriangleMesh) -> Vec3 {
+        mesh.material.albedo(&HitRecord::default())
+    }
+
+    const TRIANGLE: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+
+    #[test]
+    fn resolves_relative_and_full_corner_indices() {
+        let obj = format!(
+            "{}vt 0 0\nvt 1 0\nvt 0 1\nvn 0 0 1\nf 1/1/1 2/2/1 3/3/1\nf -3/-3/-1 -2/-2/-1 -1/-1/-1\n",
+            TRIANGLE
+        );
+        let dir = write_files("indices", &[("a.obj", &obj)]);
+        let model = load_obj(dir.join("a.obj"), grey()).unwrap();
+
+        assert_eq!(model.meshes.len(), 1);
+        let mesh = &model.meshes[0];
+        assert_eq!(mesh.indices, vec![[0, 1, 2], [0, 1, 2]]);
+        assert_eq!(mesh.uvs, vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+        assert_eq!(mesh.normals.len(), 3);
+    }
+
+    #[test]
+    fn fan_triangulates_polygons() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 2 1 0\nv 1 2 0\nv 0 1 0\nf 1 2 3 4 5\n";
+        let dir = write_files("fan", &[("a.obj", obj)]);
+        let model = load_obj(dir.join("a.obj"), grey()).unwrap();
+
+        assert_eq!(
+            model.meshes[0].indices,
+            vec![[0, 1, 2], [0, 2, 3], [0, 3, 4]]
+        );
+    }
+
+    #[test]
+    fn reports_out_of_range_index_with_line() {
+        let obj = format!("{}\nf 1 2 4\n", TRIANGLE);
+        let dir = write_files("range", &[("a.obj", &obj)]);
+
+        match load_obj(dir.join("a.obj"), grey()) {
+            Err(ObjError::Parse { line, .. }) => assert_eq!(line, 5),
+            other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn maps_map_kd_to_a_texture() {
+        // Ks > Kd would make this a metal if it weren't textured
+        let mtl = "newmtl painted\nKd 0.2 0.2 0.2\nKs 0.9 0.9 0.9\nmap_Kd green.png\n";
+        let obj = format!("mtllib a.mtl\n{}usemtl painted\nf 1 2 3\n", TRIANGLE);
+        let dir = write_files("map_kd", &[("a.mtl", mtl), ("a.obj", &obj)]);
+        image::RgbImage::from_pixel(1, 1, image::Rgb([0, 255, 0]))
+            .save(dir.join("green.png"))
+            .unwrap();
+        let model = load_obj(dir.join("a.obj"), grey()).unwrap();
+
+        assert!(model.warnings.is_empty());
+        let color = albedo(&model.meshes[0]);
+        assert!(color.x < 0.01 && color.y > 0.99 && color.z < 0.01);
+    }
+
+    #[test]
+    fn falls_back_on_unknown_material() {
+        let obj = format!("{}usemtl missing\nf 1 2 3\n", TRIANGLE);
+        let dir = write_files("unknown", &[("a.obj", &obj)]);
+        let model = load_obj(dir.join("a.obj"), grey()).unwrap();
+
+        assert_eq!(model.meshes.len(), 1);
+        let color = albedo(&model.meshes[0]);
+        assert_eq!((color.x, color.y, color.z), (0.1, 0.2, 0.3));
+        match model.warnings.as_slice() {
+            [ObjWarning::UnknownMaterial { line, name, .. }] => {
+                assert_eq!((*line, name.as_str()), (4, "missing"));
+            }
+            other => panic!("unexpected warnings {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use crate::math::interval::Interval;
+use crate::math::vec3::Vec3;
+use crate::ray::Ray;
+use crate::scene::aabb::Aabb;
+use crate::scene::hittable::{HitRecord, Hittable, LightSample};
+use crate::scene::hittable_list::HittableList;
+use crate::scene::material::Material;
+
+/// A parallelogram spanned by the edge vectors `u` and `v` from the corner `q`.
+pub struct Quad {
+    pub q: Vec3,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub material: Arc<dyn Material>,
+    w: Vec3,
+    normal: Vec3,
+    d: f64,
+    area: f64,
+    bbox: Aabb,
+}
+
+impl Quad {
+    pub fn new(q: Vec3, u: Vec3, v: Vec3, material: Arc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        let normal = n.normalized();
+        let d = normal.dot(q);
+        let w = n / n.dot(n);
+
+        let bbox_diagonal1 = Aabb::from_extrema(q, q + u + v);
+        let bbox_diagonal2 = Aabb::from_extrema(q + u, q + v);
+
+        Self {
+            q,
+            u,
+            v,
+            material,
+            w,
+            normal,
+            d,
+            area: n.length(),
+            bbox: Aabb::enclosing(bbox_diagonal1, bbox_diagonal2),
+        }
+    }
+
+    /// Returns the planar coordinates of a point on the quad's plane, given
+    /// relative to `q`, or `None` if it falls outside the parallelogram.
+    fn planar_coordinates(&self, planar_hit: Vec3) -> Option<(f64, f64)> {
+        let alpha = self.w.dot(planar_hit.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar_hit));
+
+        let unit = Interval::new(0.0, 1.0);
+        if !unit.contains(alpha) || !unit.contains(beta) {
+            return None;
+        }
+        Some((alpha, beta))
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let denom = self.normal.dot(ray.direction);
+
+        // ray is parallel to the plane
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = (self.d - self.normal.dot(ray.origin)) / denom;
+        if !ray_t.contains(t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let (u, v) = self.planar_coordinates(point - self.q)?;
+
+        Some(HitRecord::new(
+            point,
+            self.normal,
+            t,
+            self.material.clone(),
+            u,
+            v,
+            ray,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Uniform over the area, converted to a solid-angle density.
+    fn sample(&self, origin: Vec3, _time: f64, u: (f64, f64)) -> Option<LightSample> {
+        let point = self.q + u.0 * self.u + u.1 * self.v;
+        let to_point = point - origin;
+        let distance_squared = to_point.length_squared();
+        let cosine = (to_point.dot(self.normal) / distance_squared.sqrt()).abs();
+        if cosine < 1e-8 {
+            return None;
+        }
+        Some(LightSample {
+            point,
+            pdf: distance_squared / (cosine * self.area),
+        })
+    }
+
+    fn pdf_value(&self, origin: Vec3, direction: Vec3, time: f64) -> f64 {
+        let ray = Ray::new_with_time(origin, direction, time);
+        let Some(rec) = self.hit(&ray, Interval::new(0.001, f64::INFINITY)) else {
+            return 0.0;
+        };
+        let distance_squared = rec.t * rec.t * direction.length_squared();
+        let cosine = (direction.dot(self.normal) / direction.length()).abs();
+        if cosine < 1e-8 {
+            return 0.0;
+        }
+        distance_squared / (cosine * self.area)
+    }
+}
+
+/// Builds the six sides of the axis-aligned box with opposite corners `a` and `b`.
+pub fn make_box(a: Vec3, b: Vec3, material: Arc<dyn Material>) -> HittableList {
+    let mut sides = HittableList::new();
+
+    let min = Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+    let max = Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+
+    let dx = Vec3::new(max.x - min.x, 0.0, 0.0);
+    let dy = Vec3::new(0.0, max.y - min.y, 0.0);
+    let dz = Vec3::new(0.0, 0.0, max.z - min.z);
+
+    // front, right, back, left, top, bottom
+    sides.add(Arc::new(Quad::new(
+        Vec3::new(min.x, min.y, max.z),
+        dx,
+        dy,
+        material.clone(),
+    )));
+    sides.add(Arc::new(Quad::new(
+        Vec3::new(max.x, min.y, max.z),
+        -dz,
+        dy,
+        material.clone(),
+    )));
+    sides.add(Arc::new(Quad::new(
+        Vec3::new(max.x, min.y, min.z),
+        -dx,
+        dy,
+        material.clone(),
+    )));
+    sides.add(Arc::new(Quad::new(
+        Vec3::new(min.x, min.y, min.z),
+        dz,
+        dy,
+        material.clone(),
+    )));
+    sides.add(Arc::new(Quad::new(
+        Vec3::new(min.x, max.y, max.z),
+        dx,
+        -dz,
+        material.clone(),
+    )));
+    sides.add(Arc::new(Quad::new(
+        Vec3::new(min.x, min.y, min.z),
+        dx,
+        dz,
+        material,
+    )));
+
+    sides
+}
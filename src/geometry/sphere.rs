@@ -2,10 +2,12 @@ use std::f64::consts::PI;
 use std::sync::Arc;
 
 use crate::math::interval::Interval;
+use crate::math::onb::Onb;
 use crate::math::vec3::Vec3;
 use crate::ray::Ray;
+use crate::sampler::sample_uniform_sphere;
 use crate::scene::aabb::Aabb;
-use crate::scene::hittable::{HitRecord, Hittable};
+use crate::scene::hittable::{HitRecord, Hittable, LightSample};
 use crate::scene::material::Material;
 
 pub struct Sphere {
@@ -25,6 +27,8 @@ impl Sphere {
             bbox: Aabb::from_extrema(static_center - rvec, static_center + rvec),
         }
     }
+    /// A sphere whose centre moves linearly from `center1` at time 0 to
+    /// `center2` at time 1. Its bounding box only covers that interval.
     pub fn moving(center1: Vec3, center2: Vec3, radius: f64, material: Arc<dyn Material>) -> Self {
         let center = Ray::new(center1, center2 - center1);
         let rvec = Vec3::new(radius, radius, radius);
@@ -74,7 +78,7 @@ impl Hittable for Sphere {
         let point = ray.at(root);
         let outward_normal = (point - self.center.at(ray.time)) / self.radius;
         let (u, v) = Self::get_uv(&outward_normal);
-        Some(HitRecord::new(
+        let mut rec = HitRecord::new(
             point,
             outward_normal,
             root,
@@ -82,10 +86,78 @@ impl Hittable for Sphere {
             u,
             v,
             ray,
-        ))
+        );
+        rec.velocity = self.center.direction;
+        Some(rec)
     }
 
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    /// From outside, samples the cone of directions the sphere subtends;
+    /// from inside, samples its area uniformly. Moving spheres are sampled
+    /// where they are at `time`.
+    fn sample(&self, origin: Vec3, time: f64, u: (f64, f64)) -> Option<LightSample> {
+        let center = self.center.at(time);
+        let to_center = center - origin;
+        let distance_squared = to_center.length_squared();
+        let radius_squared = self.radius * self.radius;
+
+        if distance_squared <= radius_squared {
+            let normal = sample_uniform_sphere(u);
+            let point = center + normal * self.radius;
+            let to_point = point - origin;
+            let cosine = (to_point.normalized().dot(normal)).abs();
+            if cosine < 1e-8 {
+                return None;
+            }
+            let area = 4.0 * PI * radius_squared;
+            return Some(LightSample {
+                point,
+                pdf: to_point.length_squared() / (cosine * area),
+            });
+        }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let z = 1.0 + u.0 * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * u.1;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let direction = Onb::new(to_center).transform(Vec3::new(r * phi.cos(), r * phi.sin(), z));
+
+        // nearest intersection along the sampled direction; grazing rays
+        // that miss numerically land on the silhouette
+        let b = direction.dot(to_center);
+        let c = distance_squared - radius_squared;
+        let t = b - (b * b - c).max(0.0).sqrt();
+        Some(LightSample {
+            point: origin + direction * t,
+            pdf: 1.0 / (2.0 * PI * (1.0 - cos_theta_max)),
+        })
+    }
+
+    /// Mirrors `sample`: the cone density from outside, the area density
+    /// converted to solid angle from inside.
+    fn pdf_value(&self, origin: Vec3, direction: Vec3, time: f64) -> f64 {
+        let center = self.center.at(time);
+        let ray = Ray::new_with_time(origin, direction, time);
+        let Some(rec) = self.hit(&ray, Interval::new(0.001, f64::INFINITY)) else {
+            return 0.0;
+        };
+        let distance_squared = (center - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+
+        if distance_squared <= radius_squared {
+            let to_point = rec.point - origin;
+            let cosine = to_point.normalized().dot(rec.normal).abs();
+            if cosine < 1e-8 {
+                return 0.0;
+            }
+            let area = 4.0 * PI * radius_squared;
+            return to_point.length_squared() / (cosine * area);
+        }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+    }
 }
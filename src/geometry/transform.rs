@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use crate::math::interval::Interval;
+use crate::math::mat4::Mat4;
+use crate::math::vec3::Vec3;
+use crate::ray::Ray;
+use crate::scene::aabb::Aabb;
+use crate::scene::hittable::{HitRecord, Hittable, LightSample};
+
+/// Places a shared object in the world through an affine transform, so the
+/// same object can be instanced several times.
+pub struct Transform {
+    pub object: Arc<dyn Hittable>,
+    object_to_world: Mat4,
+    world_to_object: Mat4,
+    normal_to_world: Mat4,
+    /// Factor by which the transform scales volumes.
+    determinant: f64,
+    bbox: Aabb,
+}
+
+impl Transform {
+    /// Panics if `object_to_world` is not invertible.
+    pub fn new(object: Arc<dyn Hittable>, object_to_world: Mat4) -> Self {
+        let world_to_object = object_to_world
+            .inverse()
+            .expect("instance transform must be invertible");
+        let bbox = Self::transformed_bbox(object.bounding_box(), &object_to_world);
+        let axis = |v: Vec3| object_to_world.transform_vector(v);
+        let determinant = axis(Vec3::new(1.0, 0.0, 0.0))
+            .cross(axis(Vec3::new(0.0, 1.0, 0.0)))
+            .dot(axis(Vec3::new(0.0, 0.0, 1.0)))
+            .abs();
+        Self {
+            object,
+            object_to_world,
+            world_to_object,
+            normal_to_world: world_to_object.transpose(),
+            determinant,
+            bbox,
+        }
+    }
+
+    pub fn translate(object: Arc<dyn Hittable>, offset: Vec3) -> Self {
+        Self::new(object, Mat4::translation(offset))
+    }
+
+    /// Rotation in degrees about `axis` through the origin.
+    pub fn rotate(object: Arc<dyn Hittable>, axis: Vec3, degrees: f64) -> Self {
+        Self::new(object, Mat4::rotation(axis, degrees))
+    }
+
+    pub fn scale(object: Arc<dyn Hittable>, factors: Vec3) -> Self {
+        Self::new(object, Mat4::scale(factors))
+    }
+
+    /// The box around all eight transformed corners of `bbox`.
+    fn transformed_bbox(bbox: Aabb, m: &Mat4) -> Aabb {
+        let mut min = Vec3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut max = Vec3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+
+        for i in 0..8 {
+            let corner = Vec3::new(
+                if i & 1 == 0 { bbox.x.min } else { bbox.x.max },
+                if i & 2 == 0 { bbox.y.min } else { bbox.y.max },
+                if i & 4 == 0 { bbox.z.min } else { bbox.z.max },
+            );
+            let p = m.transform_point(corner);
+            min = Vec3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vec3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+        Aabb::from_extrema(min, max)
+    }
+
+    /// Ratio of the world-space to the object-space solid-angle density of
+    /// choosing `point`, with surface normal `normal`, as seen from
+    /// `origin`, all given in object space. Goes through area density,
+    /// since the transform scales a surface's area by
+    /// `determinant * |normal_to_world * normal|`.
+    fn solid_angle_scale(&self, origin: Vec3, point: Vec3, normal: Vec3) -> f64 {
+        let to_point = point - origin;
+        let normal = normal.normalized();
+        let cos_object = to_point.normalized().dot(normal).abs();
+
+        let world_to_point = self.object_to_world.transform_vector(to_point);
+        let world_normal = self.normal_to_world.transform_vector(normal);
+        let area_scale = self.determinant * world_normal.length();
+        let cos_world = world_to_point
+            .normalized()
+            .dot(world_normal.normalized())
+            .abs();
+        if cos_world < 1e-8 || area_scale == 0.0 {
+            return 0.0;
+        }
+        cos_object * world_to_point.length_squared()
+            / (to_point.length_squared() * cos_world * area_scale)
+    }
+}
+
+impl Hittable for Transform {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        // the direction is left unnormalised so `t` means the same in both spaces
+        let object_ray = Ray::new_with_time(
+            self.world_to_object.transform_point(ray.origin),
+            self.world_to_object.transform_vector(ray.direction),
+            ray.time,
+        );
+
+        let mut rec = self.object.hit(&object_ray, ray_t)?;
+
+        // normals transform by the inverse transpose, which keeps their
+        // orientation relative to the ray and so preserves front_face
+        rec.point = self.object_to_world.transform_point(rec.point);
+        rec.normal = self
+            .normal_to_world
+            .transform_vector(rec.normal)
+            .normalized();
+        rec.velocity = self.object_to_world.transform_vector(rec.velocity);
+        Some(rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn sample(&self, origin: Vec3, time: f64, u: (f64, f64)) -> Option<LightSample> {
+        let object_origin = self.world_to_object.transform_point(origin);
+        let sample = self.object.sample(object_origin, time, u)?;
+
+        // the normal at the sampled point, which sits at t = 1 along this ray
+        let ray = Ray::new_with_time(object_origin, sample.point - object_origin, time);
+        let rec = self
+            .object
+            .hit(&ray, Interval::new(1.0 - 1e-4, 1.0 + 1e-4))?;
+        let pdf = sample.pdf * self.solid_angle_scale(object_origin, sample.point, rec.normal);
+        (pdf > 0.0).then(|| LightSample {
+            point: self.object_to_world.transform_point(sample.point),
+            pdf,
+        })
+    }
+
+    fn pdf_value(&self, origin: Vec3, direction: Vec3, time: f64) -> f64 {
+        let object_ray = Ray::new_with_time(
+            self.world_to_object.transform_point(origin),
+            self.world_to_object.transform_vector(direction),
+            time,
+        );
+        let Some(rec) = self
+            .object
+            .hit(&object_ray, Interval::new(0.001, f64::INFINITY))
+        else {
+            return 0.0;
+        };
+        self.object
+            .pdf_value(object_ray.origin, object_ray.direction, time)
+            * self.solid_angle_scale(object_ray.origin, rec.point, rec.normal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::quad::Quad;
+    use crate::scene::material::lambertian::Lambertian;
+
+    #[test]
+    fn samples_lights_like_the_transformed_shape() {
+        let material = Arc::new(Lambertian::from_color(Vec3::new(1.0, 1.0, 1.0)));
+        let unit = Arc::new(Quad::new(
+            Vec3::default(),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            material.clone(),
+        ));
+        let scaled = Arc::new(Transform::scale(unit, Vec3::new(2.0, 3.0, 1.0)));
+        let rotated = Arc::new(Transform::rotate(scaled, Vec3::new(0.0, 0.0, 1.0), 90.0));
+        let light = Transform::translate(rotated, Vec3::new(0.0, 0.0, -5.0));
+        let expected = Quad::new(
+            Vec3::new(0.0, 0.0, -5.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Vec3::new(-3.0, 0.0, 0.0),
+            material,
+        );
+
+        let origin = Vec3::new(0.5, 0.7, 1.0);
+        for u in [(0.1, 0.2), (0.5, 0.5), (0.9, 0.3)] {
+            let sample = light.sample(origin, 0.0, u).unwrap();
+            let want = expected.sample(origin, 0.0, u).unwrap();
+            assert!((sample.point - want.point).length() < 1e-9);
+            assert!((sample.pdf / want.pdf - 1.0).abs() < 1e-9);
+
+            let pdf = light.pdf_value(origin, sample.point - origin, 0.0);
+            assert!((pdf / want.pdf - 1.0).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use crate::math::interval::Interval;
+use crate::math::vec3::Vec3;
+use crate::ray::Ray;
+use crate::scene::aabb::Aabb;
+use crate::scene::hittable::{HitRecord, Hittable};
+use crate::scene::material::Material;
+
+pub struct Triangle {
+    pub vertices: [Vec3; 3],
+    pub normals: Option<[Vec3; 3]>,
+    pub uvs: [(f64, f64); 3],
+    pub material: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Triangle {
+    /// Flat-shaded triangle with the default barycentric UV layout.
+    pub fn new(a: Vec3, b: Vec3, c: Vec3, material: Arc<dyn Material>) -> Self {
+        Self::with_attributes([a, b, c], None, DEFAULT_UVS, material)
+    }
+
+    /// Triangle with per-vertex shading normals and texture coordinates.
+    pub fn with_attributes(
+        vertices: [Vec3; 3],
+        normals: Option<[Vec3; 3]>,
+        uvs: [(f64, f64); 3],
+        material: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            vertices,
+            normals,
+            uvs,
+            material,
+            bbox: triangle_bbox(&vertices),
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let (t, b1, b2) = intersect(&self.vertices, ray, ray_t)?;
+        Some(surface_hit(
+            &self.vertices,
+            self.normals.as_ref(),
+            &self.uvs,
+            t,
+            b1,
+            b2,
+            self.material.clone(),
+            ray,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+/// UVs used when a triangle has no texture coordinates of its own.
+pub(crate) const DEFAULT_UVS: [(f64, f64); 3] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)];
+
+pub(crate) fn triangle_bbox(p: &[Vec3; 3]) -> Aabb {
+    Aabb::enclosing(
+        Aabb::from_extrema(p[0], p[1]),
+        Aabb::from_extrema(p[1], p[2]),
+    )
+}
+
+/// Möller–Trumbore intersection. Returns `t` and the barycentric weights of
+/// the second and third vertices.
+pub(crate) fn intersect(p: &[Vec3; 3], ray: &Ray, ray_t: Interval) -> Option<(f64, f64, f64)> {
+    let e1 = p[1] - p[0];
+    let e2 = p[2] - p[0];
+
+    let pvec = ray.direction.cross(e2);
+    let det = e1.dot(pvec);
+
+    // ray is parallel to the triangle
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let tvec = ray.origin - p[0];
+    let b1 = tvec.dot(pvec) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let qvec = tvec.cross(e1);
+    let b2 = ray.direction.dot(qvec) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = e2.dot(qvec) * inv_det;
+    if !ray_t.surrounds(t) {
+        return None;
+    }
+    Some((t, b1, b2))
+}
+
+/// Builds the hit record for a triangle hit, interpolating shading normals
+/// and texture coordinates from the barycentric weights.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn surface_hit(
+    p: &[Vec3; 3],
+    normals: Option<&[Vec3; 3]>,
+    uvs: &[(f64, f64); 3],
+    t: f64,
+    b1: f64,
+    b2: f64,
+    material: Arc<dyn Material>,
+    ray: &Ray,
+) -> HitRecord {
+    let b0 = 1.0 - b1 - b2;
+    let geometric_normal = (p[1] - p[0]).cross(p[2] - p[0]).normalized();
+
+    let u = b0 * uvs[0].0 + b1 * uvs[1].0 + b2 * uvs[2].0;
+    let v = b0 * uvs[0].1 + b1 * uvs[1].1 + b2 * uvs[2].1;
+
+    let mut rec = HitRecord::new(ray.at(t), geometric_normal, t, material, u, v, ray);
+
+    if let Some(n) = normals {
+        // front_face stays tied to the geometric normal; only the shading
+        // normal is smoothed, oriented to the same side as the geometry
+        let mut shading = (b0 * n[0] + b1 * n[1] + b2 * n[2]).normalized();
+        if shading.dot(geometric_normal) < 0.0 {
+            shading = -shading;
+        }
+        rec.normal = if rec.front_face { shading } else { -shading };
+    }
+    rec
+}
@@ -1,13 +1,22 @@
-use crate::math::interval::Interval;
+use crate::image::pfm;
+use crate::image::tonemap::OutputTransform;
 use crate::math::vec3::Vec3;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::pnm::{PnmEncoder, PnmSubtype, SampleEncoding};
+use image::{ImageFormat, ImageResult, Rgb32FImage, RgbImage};
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufReader, BufWriter, Write};
 use std::path::Path;
 
+/// Quality used for JPEG output, from 1 to 100.
+const JPEG_QUALITY: u8 = 90;
+
 pub struct ImageBuffer {
     pub width: usize,
     pub height: usize,
     pub pixels: Vec<Vec3>, // store color as Vec3
+    /// How the 8-bit writers turn the linear pixels into display values.
+    pub output: OutputTransform,
 }
 
 impl ImageBuffer {
@@ -17,6 +26,7 @@ impl ImageBuffer {
             width,
             height,
             pixels,
+            output: OutputTransform::default(),
         }
     }
 
@@ -26,31 +36,98 @@ impl ImageBuffer {
         }
     }
 
-    fn linear_to_gamma(linear_component: f64) -> f64 {
-        if linear_component > 0.0 {
-            linear_component.sqrt()
-        } else {
-            0.0
+    /// The image as 8-bit sRGB, passed through `output`.
+    pub fn to_rgb8(&self) -> RgbImage {
+        let mut rgb = RgbImage::new(self.width as u32, self.height as u32);
+        for (out, &color) in rgb.pixels_mut().zip(&self.pixels) {
+            out.0 = self.output.to_bytes(color);
+        }
+        rgb
+    }
+
+    /// Loads an image as linear colour. Float formats (OpenEXR, Radiance
+    /// `.hdr`, PFM) keep their values; 8-bit formats are scaled to [0, 1]
+    /// without decoding their gamma.
+    pub fn load<P: AsRef<Path>>(path: P) -> ImageResult<Self> {
+        let path = path.as_ref();
+        if is_pfm(path) {
+            return pfm::read(BufReader::new(File::open(path)?));
+        }
+        let image = image::open(path)?.to_rgb32f();
+        let (width, height) = image.dimensions();
+        let mut img = Self::new(width as usize, height as usize);
+        for (pixel, p) in img.pixels.iter_mut().zip(image.pixels()) {
+            *pixel = Vec3::new(p[0] as f64, p[1] as f64, p[2] as f64);
+        }
+        Ok(img)
+    }
+
+    /// The image as 32-bit float RGB, unclamped.
+    pub fn to_rgb32f(&self) -> Rgb32FImage {
+        let mut rgb = Rgb32FImage::new(self.width as u32, self.height as u32);
+        for (out, color) in rgb.pixels_mut().zip(&self.pixels) {
+            out.0 = [color.x as f32, color.y as f32, color.z as f32];
+        }
+        rgb
+    }
+
+    /// Whether `path` names a format `save` can write.
+    pub fn can_save<P: AsRef<Path>>(path: P) -> bool {
+        is_pfm(path.as_ref()) || ImageFormat::from_path(path).is_ok()
+    }
+
+    /// Writes the image in the format named by the path's extension. The
+    /// float formats, OpenEXR (`.exr`), Radiance (`.hdr`) and PFM (`.pfm`),
+    /// keep the linear values as they are. Everything else goes through
+    /// `output` to 8-bit sRGB: PNG, JPEG, TGA, binary PPM (`.ppm`), or
+    /// anything else the `image` crate can encode as RGB.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> ImageResult<()> {
+        let path = path.as_ref();
+        if is_pfm(path) {
+            return pfm::write(self, BufWriter::new(File::create(path)?));
+        }
+        let format = ImageFormat::from_path(path)?;
+        if matches!(format, ImageFormat::OpenExr | ImageFormat::Hdr) {
+            return self.to_rgb32f().save_with_format(path, format);
+        }
+
+        let rgb = self.to_rgb8();
+        match format {
+            ImageFormat::Jpeg => {
+                let file = BufWriter::new(File::create(path)?);
+                JpegEncoder::new_with_quality(file, JPEG_QUALITY).encode_image(&rgb)
+            }
+            ImageFormat::Pnm => {
+                let file = BufWriter::new(File::create(path)?);
+                PnmEncoder::new(file)
+                    .with_subtype(PnmSubtype::Pixmap(SampleEncoding::Binary))
+                    .encode(
+                        rgb.as_raw().as_slice(),
+                        rgb.width(),
+                        rgb.height(),
+                        image::ExtendedColorType::Rgb8,
+                    )
+            }
+            format => rgb.save_with_format(path, format),
         }
     }
 
+    /// Writes the image as ASCII (P3) PPM.
     pub fn write_ppm<P: AsRef<Path>>(&self, path: P) {
         let mut file = File::create(path).unwrap();
         write!(file, "P3\n{} {}\n255\n", self.width, self.height).unwrap();
-        const INTENSITY: Interval = Interval::new(0.0, 0.99);
         for y in 0..self.height {
             for x in 0..self.width {
-                let color = self.pixels[y * self.width + x];
-                let r = Self::linear_to_gamma(color.x);
-                let g = Self::linear_to_gamma(color.y);
-                let b = Self::linear_to_gamma(color.z);
-
-                let r_byte = (256.0 * INTENSITY.clamp(r)) as u8;
-                let g_byte = (256.0 * INTENSITY.clamp(g)) as u8;
-                let b_byte = (256.0 * INTENSITY.clamp(b)) as u8;
+                let [r_byte, g_byte, b_byte] =
+                    self.output.to_bytes(self.pixels[y * self.width + x]);
                 write!(file, "{} {} {} ", r_byte, g_byte, b_byte).unwrap();
             }
-            write!(file, "\n").unwrap();
+            writeln!(file).unwrap();
         }
     }
 }
+
+fn is_pfm(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pfm"))
+}
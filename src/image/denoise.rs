@@ -0,0 +1,168 @@
+//! Edge-avoiding à-trous wavelet denoising (Dammertz et al., "Edge-Avoiding
+//! À-Trous Wavelet Transform for fast Global Illumination Filtering", 2010).
+//!
+//! Each pass blurs with a 5×5 B-spline kernel whose taps are spread further
+//! apart every pass, and weights every tap by how closely its colour, normal
+//! and albedo match the centre pixel's, so the blur stops at geometric and
+//! texture edges. The colour is divided by the albedo beforehand and
+//! multiplied back afterwards, which keeps texture out of the blur.
+
+use crate::image::buffer::ImageBuffer;
+use crate::math::vec3::Vec3;
+
+/// Weights of the one-dimensional B3-spline kernel.
+const KERNEL: [f64; 5] = [1.0 / 16.0, 1.0 / 4.0, 3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+/// Albedo below which a channel is left as it is rather than divided out.
+const MIN_ALBEDO: f64 = 1e-3;
+
+pub struct Denoiser {
+    /// Number of filter passes. Pass `i` spaces its taps `2^i` pixels apart,
+    /// so five passes reach 62 pixels from the centre.
+    pub iterations: usize,
+    /// Difference of compressed colours, each channel `c` taken as
+    /// `c / (1 + c)`, at which a tap's weight falls to `1/e` in the first
+    /// pass; halved every pass as the noise it must tolerate shrinks.
+    pub color_sigma: f64,
+    /// Normal difference at which a tap's weight falls to `1/e`.
+    pub normal_sigma: f64,
+    /// Albedo difference at which a tap's weight falls to `1/e`.
+    pub albedo_sigma: f64,
+}
+
+impl Denoiser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a denoised copy of `img`, guided by the `albedo` and `normal`
+    /// buffers of the same render. Taps with non-finite values are skipped.
+    ///
+    /// # Panics
+    ///
+    /// If `albedo` or `normal` does not have `img`'s dimensions.
+    pub fn denoise(
+        &self,
+        img: &ImageBuffer,
+        albedo: &ImageBuffer,
+        normal: &ImageBuffer,
+    ) -> ImageBuffer {
+        let (width, height) = (img.width, img.height);
+        for guide in [albedo, normal] {
+            assert!(
+                guide.width == width && guide.height == height,
+                "guide buffers must match the image's dimensions"
+            );
+        }
+        let mut irradiance: Vec<Vec3> = img
+            .pixels
+            .iter()
+            .zip(&albedo.pixels)
+            .map(|(&color, &albedo)| demodulate(color, albedo))
+            .collect();
+
+        let mut filtered = vec![Vec3::default(); irradiance.len()];
+        for pass in 0..self.iterations {
+            let step = 1isize << pass;
+            let color_sigma = self.color_sigma / (1u64 << pass) as f64;
+            let compressed: Vec<Vec3> = irradiance.iter().map(|&c| compress(c)).collect();
+            for y in 0..height {
+                for x in 0..width {
+                    let p = y * width + x;
+                    let mut sum = Vec3::default();
+                    let mut total = 0.0;
+                    for (dy, ky) in KERNEL.iter().enumerate() {
+                        let qy = y as isize + (dy as isize - 2) * step;
+                        if qy < 0 || qy >= height as isize {
+                            continue;
+                        }
+                        for (dx, kx) in KERNEL.iter().enumerate() {
+                            let qx = x as isize + (dx as isize - 2) * step;
+                            if qx < 0 || qx >= width as isize {
+                                continue;
+                            }
+                            let q = qy as usize * width + qx as usize;
+                            if !is_finite(irradiance[q]) {
+                                continue;
+                            }
+                            let weight = kx
+                                * ky
+                                * similarity(compressed[p], compressed[q], color_sigma)
+                                * similarity(normal.pixels[p], normal.pixels[q], self.normal_sigma)
+                                * similarity(albedo.pixels[p], albedo.pixels[q], self.albedo_sigma);
+                            if !weight.is_finite() {
+                                continue;
+                            }
+                            sum = sum + irradiance[q] * weight;
+                            total += weight;
+                        }
+                    }
+                    filtered[p] = if total > 0.0 {
+                        sum / total
+                    } else {
+                        irradiance[p]
+                    };
+                }
+            }
+            std::mem::swap(&mut irradiance, &mut filtered);
+        }
+
+        let mut out = ImageBuffer::new(width, height);
+        out.output = img.output;
+        for ((out, &irradiance), &albedo) in
+            out.pixels.iter_mut().zip(&irradiance).zip(&albedo.pixels)
+        {
+            *out = remodulate(irradiance, albedo);
+        }
+        out
+    }
+}
+
+impl Default for Denoiser {
+    fn default() -> Self {
+        Self {
+            iterations: 5,
+            color_sigma: 0.2,
+            normal_sigma: 0.1,
+            albedo_sigma: 0.1,
+        }
+    }
+}
+
+/// Gaussian falloff in the distance between `a` and `b`.
+fn similarity(a: Vec3, b: Vec3, sigma: f64) -> f64 {
+    (-(a - b).length_squared() / (sigma * sigma)).exp()
+}
+
+fn demodulate(color: Vec3, albedo: Vec3) -> Vec3 {
+    let divide = |c: f64, a: f64| if a > MIN_ALBEDO { c / a } else { c };
+    Vec3::new(
+        divide(color.x, albedo.x),
+        divide(color.y, albedo.y),
+        divide(color.z, albedo.z),
+    )
+}
+
+fn remodulate(irradiance: Vec3, albedo: Vec3) -> Vec3 {
+    let multiply = |c: f64, a: f64| if a > MIN_ALBEDO { c * a } else { c };
+    Vec3::new(
+        multiply(irradiance.x, albedo.x),
+        multiply(irradiance.y, albedo.y),
+        multiply(irradiance.z, albedo.z),
+    )
+}
+
+/// Maps each channel `c` to `c / (1 + c)`, so colour differences stay
+/// bounded and bright outliers still blend with their neighbours. Negative
+/// values, which ringing filters produce at edges, count as 0.
+fn compress(c: Vec3) -> Vec3 {
+    let compress = |c: f64| {
+        let c = c.max(0.0);
+        c / (1.0 + c)
+    };
+    Vec3::new(compress(c.x), compress(c.y), compress(c.z))
+}
+
+fn is_finite(c: Vec3) -> bool {
+    c.x.is_finite() && c.y.is_finite() && c.z.is_finite()
+}
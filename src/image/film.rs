@@ -0,0 +1,101 @@
+use crate::image::buffer::ImageBuffer;
+use crate::image::filter::Filter;
+use crate::math::vec3::Vec3;
+
+/// Accumulates filtered radiance samples per pixel. A film may cover just
+/// a window of the image starting at pixel (`x0`, `y0`), so tiles can
+/// splat independently and be merged afterwards. Several renders can add to
+/// the same film; `image` resolves the weighted average so far.
+pub struct Film {
+    pub x0: usize,
+    pub y0: usize,
+    pub width: usize,
+    pub height: usize,
+    sums: Vec<Vec3>,
+    weights: Vec<f64>,
+}
+
+impl Film {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::window(0, 0, width, height)
+    }
+
+    /// A film for the `width` by `height` pixels starting at (`x0`, `y0`).
+    pub fn window(x0: usize, y0: usize, width: usize, height: usize) -> Self {
+        Self {
+            x0,
+            y0,
+            width,
+            height,
+            sums: vec![Vec3::default(); width * height],
+            weights: vec![0.0; width * height],
+        }
+    }
+
+    /// Adds a sample taken at image position (`x`, `y`), where pixel `i`'s
+    /// centre is at `i`, to every pixel in the filter's reach. A pixel
+    /// exactly `radius` away on the low side is left out, so samples on a
+    /// shared edge aren't counted twice by a box filter.
+    pub fn splat(&mut self, x: f64, y: f64, color: Vec3, filter: &dyn Filter) {
+        let radius = filter.radius();
+        let x_range = self.reach(x, radius, self.x0, self.width);
+        let y_range = self.reach(y, radius, self.y0, self.height);
+        for py in y_range {
+            for px in x_range.clone() {
+                let weight = filter.evaluate(px as f64 - x, py as f64 - y);
+                if weight != 0.0 {
+                    let index = (py - self.y0) * self.width + (px - self.x0);
+                    self.sums[index] = self.sums[index] + color * weight;
+                    self.weights[index] += weight;
+                }
+            }
+        }
+    }
+
+    /// Pixel indices in `(p - radius, p + radius]` on one axis, clipped to
+    /// the film's window `[start, start + len)`.
+    fn reach(&self, p: f64, radius: f64, start: usize, len: usize) -> std::ops::Range<usize> {
+        let low = ((p - radius).floor() + 1.0).max(start as f64);
+        let high = ((p + radius).floor() + 1.0).min((start + len) as f64);
+        if high <= low {
+            return 0..0;
+        }
+        low as usize..high as usize
+    }
+
+    /// Adds everything splatted into `other` to the overlapping pixels of
+    /// this film.
+    pub fn merge(&mut self, other: &Film) {
+        for y in 0..other.height {
+            let fy = other.y0 + y;
+            if fy < self.y0 || fy >= self.y0 + self.height {
+                continue;
+            }
+            for x in 0..other.width {
+                let fx = other.x0 + x;
+                if fx < self.x0 || fx >= self.x0 + self.width {
+                    continue;
+                }
+                let from = y * other.width + x;
+                let to = (fy - self.y0) * self.width + (fx - self.x0);
+                self.sums[to] = self.sums[to] + other.sums[from];
+                self.weights[to] += other.weights[from];
+            }
+        }
+    }
+
+    /// Each pixel's weighted mean; black where nothing landed.
+    pub fn image(&self) -> ImageBuffer {
+        let mut img = ImageBuffer::new(self.width, self.height);
+        for (pixel, (&sum, &weight)) in img
+            .pixels
+            .iter_mut()
+            .zip(self.sums.iter().zip(&self.weights))
+        {
+            if weight != 0.0 {
+                *pixel = sum / weight;
+            }
+        }
+        img
+    }
+}
@@ -0,0 +1,28 @@
+use crate::image::filter::Filter;
+
+/// Equal weight over a square. A radius of 0.5 averages exactly the
+/// samples taken inside each pixel.
+#[derive(Clone, Copy, Debug)]
+pub struct BoxFilter {
+    radius: f64,
+}
+
+impl BoxFilter {
+    pub fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+}
+
+impl Filter for BoxFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64, y: f64) -> f64 {
+        if x.abs() <= self.radius && y.abs() <= self.radius {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::image::filter::Filter;
+
+/// Gaussian of standard deviation `sigma`, shifted down so it reaches zero
+/// at the radius instead of being cut off abruptly.
+#[derive(Clone, Copy, Debug)]
+pub struct GaussianFilter {
+    radius: f64,
+    sigma: f64,
+    edge: f64,
+}
+
+impl GaussianFilter {
+    pub fn new(radius: f64, sigma: f64) -> Self {
+        Self {
+            radius,
+            sigma,
+            edge: gaussian(radius, sigma),
+        }
+    }
+}
+
+fn gaussian(x: f64, sigma: f64) -> f64 {
+    (-x * x / (2.0 * sigma * sigma)).exp()
+}
+
+impl Filter for GaussianFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64, y: f64) -> f64 {
+        let gx = (gaussian(x, self.sigma) - self.edge).max(0.0);
+        let gy = (gaussian(y, self.sigma) - self.edge).max(0.0);
+        gx * gy
+    }
+}
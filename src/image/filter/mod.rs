@@ -0,0 +1,20 @@
+//! Pixel reconstruction filters.
+//!
+//! The film splats every camera sample into each pixel whose centre lies
+//! within the filter's radius, weighted by the filter evaluated at the
+//! offset from the sample to that centre, and divides by the total weight.
+
+pub mod box_filter;
+pub mod gaussian;
+pub mod lanczos;
+pub mod mitchell;
+pub mod tent;
+
+pub trait Filter: Sync + Send {
+    /// Half-width of the filter's support in pixels, on both axes.
+    fn radius(&self) -> f64;
+
+    /// Weight of a sample offset by (`x`, `y`) pixels from a pixel centre.
+    /// May be negative for filters that sharpen.
+    fn evaluate(&self, x: f64, y: f64) -> f64;
+}
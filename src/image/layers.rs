@@ -0,0 +1,64 @@
+//! Multi-layer OpenEXR: the main image as `R`, `G` and `B`, and each extra
+//! layer as channels named `<layer>.<channel>`, the grouping compositing
+//! packages show as separate layers.
+
+use std::path::Path;
+
+use exr::prelude::{
+    AnyChannel, AnyChannels, Encoding, FlatSamples, LayerAttributes, SmallVec, WritableImage,
+};
+use image::error::{EncodingError, ImageFormatHint};
+use image::{ImageError, ImageFormat, ImageResult};
+
+use crate::image::buffer::ImageBuffer;
+
+/// A named image rendered alongside the main one, such as an AOV.
+pub struct Layer {
+    pub name: String,
+    /// Names of the channels stored in each pixel's x, y and z, in that
+    /// order; components without a name are not written to EXR.
+    pub channels: &'static [&'static str],
+    pub image: ImageBuffer,
+}
+
+/// Writes `img` and `layers`, which must all have its dimensions, as one
+/// 32-bit float OpenEXR file.
+pub fn write_exr<P: AsRef<Path>>(path: P, img: &ImageBuffer, layers: &[&Layer]) -> ImageResult<()> {
+    let mut channels = SmallVec::new();
+    let mut add = |name: String, image: &ImageBuffer, component: usize| {
+        let samples = image
+            .pixels
+            .iter()
+            .map(|color| [color.x, color.y, color.z][component] as f32)
+            .collect();
+        channels.push(AnyChannel::new(name.as_str(), FlatSamples::F32(samples)));
+    };
+    for (component, name) in ["R", "G", "B"].into_iter().enumerate() {
+        add(name.to_string(), img, component);
+    }
+    for layer in layers {
+        for (component, channel) in layer.channels.iter().take(3).enumerate() {
+            add(
+                format!("{}.{}", layer.name, channel),
+                &layer.image,
+                component,
+            );
+        }
+    }
+
+    let layer = exr::prelude::Layer::new(
+        (img.width, img.height),
+        LayerAttributes::default(),
+        Encoding::FAST_LOSSLESS,
+        AnyChannels::sort(channels),
+    );
+    exr::prelude::Image::from_layer(layer)
+        .write()
+        .to_file(path)
+        .map_err(|err| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::OpenExr),
+                err,
+            ))
+        })
+}
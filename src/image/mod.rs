@@ -1,4 +1,10 @@
 pub mod buffer;
+pub mod denoise;
+pub mod film;
+pub mod filter;
+pub mod layers;
+pub mod pfm;
+pub mod tonemap;
 
 pub fn srgb_to_linear(c: f64) -> f64 {
     if c <= 0.04045 {
@@ -7,3 +13,12 @@ pub fn srgb_to_linear(c: f64) -> f64 {
         ((c + 0.055) / 1.055).powf(2.4)
     }
 }
+
+/// The exact sRGB transfer function, encoding a linear value in [0, 1].
+pub fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,167 @@
+//! Portable Float Map: a PPM-like header followed by raw 32-bit floats,
+//! rows stored bottom to top. The sign of the header's scale gives the
+//! byte order (negative for little-endian).
+
+use std::io::{BufRead, Read, Write};
+
+use image::error::{DecodingError, ImageFormatHint};
+use image::{ImageError, ImageResult};
+
+use crate::image::buffer::ImageBuffer;
+use crate::math::vec3::Vec3;
+
+/// Writes colour (`PF`) little-endian PFM.
+pub fn write<W: Write>(img: &ImageBuffer, mut writer: W) -> ImageResult<()> {
+    write!(writer, "PF\n{} {}\n-1.0\n", img.width, img.height)?;
+    for y in (0..img.height).rev() {
+        for color in &img.pixels[y * img.width..(y + 1) * img.width] {
+            for c in [color.x, color.y, color.z] {
+                writer.write_all(&(c as f32).to_le_bytes())?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads colour (`PF`) or greyscale (`Pf`) PFM of either byte order.
+pub fn read<R: BufRead>(mut reader: R) -> ImageResult<ImageBuffer> {
+    let magic = header_token(&mut reader)?;
+    let channels = match magic.as_str() {
+        "PF" => 3,
+        "Pf" => 1,
+        _ => return Err(decoding_error("not a PFM file")),
+    };
+    let width = parse_token::<usize, _>(&mut reader)?;
+    let height = parse_token::<usize, _>(&mut reader)?;
+    let scale = parse_token::<f64, _>(&mut reader)?;
+    if width == 0 || height == 0 {
+        return Err(decoding_error("image has no pixels"));
+    }
+    if !scale.is_finite() || scale == 0.0 {
+        return Err(decoding_error("invalid scale"));
+    }
+    let little_endian = scale < 0.0;
+
+    let size = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(channels * 4))
+        .ok_or_else(|| decoding_error("image dimensions too large"))?;
+    // read through `take` so the buffer only grows as data actually
+    // arrives, rather than trusting the header with one big allocation
+    let mut data = Vec::new();
+    reader.take(size as u64).read_to_end(&mut data)?;
+    if data.len() != size {
+        return Err(decoding_error("pixel data is truncated"));
+    }
+    let values: Vec<f64> = data
+        .chunks_exact(4)
+        .map(|b| {
+            let bytes = [b[0], b[1], b[2], b[3]];
+            if little_endian {
+                f32::from_le_bytes(bytes) as f64
+            } else {
+                f32::from_be_bytes(bytes) as f64
+            }
+        })
+        .collect();
+
+    let mut img = ImageBuffer::new(width, height);
+    for (k, texel) in values.chunks_exact(channels).enumerate() {
+        let (x, y) = (k % width, height - 1 - k / width);
+        let color = match texel {
+            [r, g, b] => Vec3::new(*r, *g, *b),
+            _ => Vec3::new(texel[0], texel[0], texel[0]),
+        };
+        img.set_pixel(x, y, color);
+    }
+    Ok(img)
+}
+
+/// The next whitespace-delimited header field. Consumes exactly one
+/// whitespace byte after it, so the pixel data starts right after the scale.
+fn header_token<R: BufRead>(reader: &mut R) -> ImageResult<String> {
+    // longer than any number a valid header holds
+    const MAX_TOKEN: usize = 64;
+    let mut token = String::new();
+    let mut byte = [0u8];
+    loop {
+        reader.read_exact(&mut byte)?;
+        if byte[0].is_ascii_whitespace() {
+            if token.is_empty() {
+                continue;
+            }
+            return Ok(token);
+        }
+        if token.len() == MAX_TOKEN {
+            return Err(decoding_error("header field too long"));
+        }
+        token.push(byte[0] as char);
+    }
+}
+
+fn parse_token<T: std::str::FromStr, R: BufRead>(reader: &mut R) -> ImageResult<T> {
+    let token = header_token(reader)?;
+    token
+        .parse()
+        .map_err(|_| decoding_error(&format!("invalid header field '{}'", token)))
+}
+
+fn decoding_error(message: &str) -> ImageError {
+    ImageError::Decoding(DecodingError::new(
+        ImageFormatHint::Name("PFM".to_string()),
+        message.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_image() -> ImageBuffer {
+        let mut img = ImageBuffer::new(3, 2);
+        for (k, pixel) in img.pixels.iter_mut().enumerate() {
+            let k = k as f64;
+            *pixel = Vec3::new(k, -0.5 * k, 1e3 + k);
+        }
+        img
+    }
+
+    #[test]
+    fn round_trips_little_endian() {
+        let img = test_image();
+        let mut bytes = vec![];
+        write(&img, &mut bytes).unwrap();
+        let read_back = read(bytes.as_slice()).unwrap();
+        assert_eq!((read_back.width, read_back.height), (3, 2));
+        assert_eq!(read_back.pixels, img.pixels);
+    }
+
+    #[test]
+    fn reads_big_endian() {
+        let img = test_image();
+        let mut bytes = b"PF\n3 2\n1.0\n".to_vec();
+        for y in (0..img.height).rev() {
+            for color in &img.pixels[y * img.width..(y + 1) * img.width] {
+                for c in [color.x, color.y, color.z] {
+                    bytes.extend_from_slice(&(c as f32).to_be_bytes());
+                }
+            }
+        }
+        let read_back = read(bytes.as_slice()).unwrap();
+        assert_eq!(read_back.pixels, img.pixels);
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        let mut truncated = vec![];
+        write(&test_image(), &mut truncated).unwrap();
+        truncated.truncate(truncated.len() - 1);
+        assert!(read(truncated.as_slice()).is_err());
+
+        let overflowing = format!("PF\n{} {}\n-1.0\n", usize::MAX, usize::MAX);
+        assert!(read(overflowing.as_bytes()).is_err());
+        assert!(read(&b"PF\n100000 100000\n-1.0\n\0\0\0\0"[..]).is_err());
+        assert!(read(&b"PF\n0 4\n-1.0\n"[..]).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::image::linear_to_srgb;
+use crate::math::vec3::Vec3;
+
+/// Operator compressing scene radiance into the displayable [0, 1] range.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ToneMap {
+    /// Clamps each channel to [0, 1].
+    #[default]
+    Clip,
+    /// `L / (1 + L)` on luminance, keeping hue; never reaches white.
+    Reinhard,
+    /// Reinhard with a white point: luminance `white` and above maps to 1.
+    ExtendedReinhard { white: f64 },
+    /// Stephen Hill's fit of the ACES reference rendering and sRGB output
+    /// transforms, with its film-like toe and shoulder.
+    Aces,
+    /// Troy Sobotka's AgX, which desaturates bright colours towards white
+    /// instead of skewing their hue; uses the polynomial fit of its contrast
+    /// curve.
+    Agx,
+}
+
+impl ToneMap {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "clip" => Some(ToneMap::Clip),
+            "reinhard" => Some(ToneMap::Reinhard),
+            "extended_reinhard" => Some(ToneMap::ExtendedReinhard { white: 4.0 }),
+            "aces" => Some(ToneMap::Aces),
+            "agx" => Some(ToneMap::Agx),
+            _ => None,
+        }
+    }
+
+    /// Maps a linear scene colour to a linear display colour in [0, 1].
+    pub fn apply(self, color: Vec3) -> Vec3 {
+        let mapped = match self {
+            ToneMap::Clip => color,
+            ToneMap::Reinhard => scale_luminance(color, |l| l / (1.0 + l)),
+            ToneMap::ExtendedReinhard { white } => {
+                scale_luminance(color, |l| l * (1.0 + l / (white * white)) / (1.0 + l))
+            }
+            ToneMap::Aces => aces(color),
+            ToneMap::Agx => agx(color),
+        };
+        Vec3::new(
+            mapped.x.clamp(0.0, 1.0),
+            mapped.y.clamp(0.0, 1.0),
+            mapped.z.clamp(0.0, 1.0),
+        )
+    }
+}
+
+/// How linear radiance becomes 8-bit display values: an exposure
+/// adjustment, a tone mapping operator and the sRGB transfer function.
+/// It is applied to the finished image, after any physical
+/// `Camera::exposure` has already scaled the radiance.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OutputTransform {
+    /// Exposure adjustment in stops; each stop doubles the radiance.
+    pub exposure_stops: f64,
+    pub tone_map: ToneMap,
+}
+
+impl OutputTransform {
+    pub fn new(exposure_stops: f64, tone_map: ToneMap) -> Self {
+        Self {
+            exposure_stops,
+            tone_map,
+        }
+    }
+
+    /// Maps a linear colour to sRGB-encoded values in [0, 1].
+    pub fn apply(&self, color: Vec3) -> Vec3 {
+        let display = self.tone_map.apply(color * 2f64.powf(self.exposure_stops));
+        Vec3::new(
+            linear_to_srgb(display.x),
+            linear_to_srgb(display.y),
+            linear_to_srgb(display.z),
+        )
+    }
+
+    /// Maps a linear colour to 8-bit sRGB.
+    pub fn to_bytes(&self, color: Vec3) -> [u8; 3] {
+        let encoded = self.apply(color);
+        let byte = |c: f64| (255.0 * c + 0.5) as u8;
+        [byte(encoded.x), byte(encoded.y), byte(encoded.z)]
+    }
+}
+
+/// Scales `color` so its luminance becomes `curve(luminance)`.
+fn scale_luminance(color: Vec3, curve: impl Fn(f64) -> f64) -> Vec3 {
+    let luminance = color.luminance();
+    if luminance <= 0.0 {
+        return Vec3::default();
+    }
+    color * (curve(luminance) / luminance)
+}
+
+fn mul(m: &[[f64; 3]; 3], v: Vec3) -> Vec3 {
+    Vec3::new(
+        m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+        m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+        m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+    )
+}
+
+fn aces(color: Vec3) -> Vec3 {
+    // sRGB to the ACES rendering space, with the RRT's saturation tweak.
+    const INPUT: [[f64; 3]; 3] = [
+        [0.59719, 0.35458, 0.04823],
+        [0.07600, 0.90834, 0.01566],
+        [0.02840, 0.13383, 0.83777],
+    ];
+    // Back to linear sRGB after the ODT.
+    const OUTPUT: [[f64; 3]; 3] = [
+        [1.60475, -0.53108, -0.07367],
+        [-0.10208, 1.10813, -0.00605],
+        [-0.00327, -0.07276, 1.07602],
+    ];
+    let curve =
+        |v: f64| (v * (v + 0.0245786) - 0.000090537) / (v * (0.983729 * v + 0.4329510) + 0.238081);
+    let v = mul(&INPUT, color);
+    mul(&OUTPUT, Vec3::new(curve(v.x), curve(v.y), curve(v.z)))
+}
+
+fn agx(color: Vec3) -> Vec3 {
+    // Linear sRGB to the AgX working space, which pulls primaries inwards
+    // so bright saturated colours run to white.
+    const INSET: [[f64; 3]; 3] = [
+        [0.842479062253094, 0.0784335999999992, 0.0792237451477643],
+        [0.0423282422610123, 0.878468636469772, 0.0791661274605434],
+        [0.0423756549057051, 0.0784336, 0.879142973793104],
+    ];
+    const OUTSET: [[f64; 3]; 3] = [
+        [1.19687900512017, -0.0980208811401368, -0.0990297440797205],
+        [-0.0528968517574562, 1.15190312990417, -0.0989611768448433],
+        [-0.0529716355144438, -0.0980434501171241, 1.15107367264116],
+    ];
+    // Exposure range, in stops around middle grey, spread over [0, 1].
+    const MIN_EV: f64 = -12.47393;
+    const MAX_EV: f64 = 4.026069;
+
+    let curve = |v: f64| {
+        let x = (v.max(1e-10).log2().clamp(MIN_EV, MAX_EV) - MIN_EV) / (MAX_EV - MIN_EV);
+        let x2 = x * x;
+        let x4 = x2 * x2;
+        15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x
+            - 0.00232
+    };
+    let v = mul(&INSET, color);
+    let encoded = mul(&OUTSET, Vec3::new(curve(v.x), curve(v.y), curve(v.z)));
+    // The curve targets a 2.2 gamma display; undo that to stay linear.
+    let linear = |c: f64| c.max(0.0).powf(2.2);
+    Vec3::new(linear(encoded.x), linear(encoded.y), linear(encoded.z))
+}
@@ -0,0 +1,131 @@
+use crate::{
+    integrator::Integrator,
+    math::{interval::Interval, vec3::Vec3},
+    ray::Ray,
+    sampler::Sampler,
+    scene::{bvh, world::World},
+};
+
+/// What a `DebugIntegrator` shows at the first hit of each camera ray.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugMode {
+    /// Outward-facing normal, mapped from [-1, 1] to [0, 1] per axis.
+    Normals,
+    /// Hit distance `t` divided by `max_distance`; black on a miss.
+    Depth,
+    /// Surface coordinates as red (`u`) and green (`v`).
+    Uv,
+    /// The material's albedo; the background on a miss.
+    Albedo,
+    /// Green where the ray hits the front of a surface, red for the back.
+    FrontFace,
+    /// BVH nodes tested while finding the hit, as a blue-to-red heatmap
+    /// reaching red at `max_visits`.
+    BvhVisits,
+}
+
+impl DebugMode {
+    /// Parses the names accepted on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "normals" => Some(DebugMode::Normals),
+            "depth" => Some(DebugMode::Depth),
+            "uv" => Some(DebugMode::Uv),
+            "albedo" => Some(DebugMode::Albedo),
+            "front_face" => Some(DebugMode::FrontFace),
+            "bvh" => Some(DebugMode::BvhVisits),
+            _ => None,
+        }
+    }
+}
+
+/// Visualises geometry and acceleration-structure data instead of light.
+pub struct DebugIntegrator {
+    pub mode: DebugMode,
+    /// Hit distance shown as white in `DebugMode::Depth`.
+    pub max_distance: f64,
+    /// Node visit count shown as full red in `DebugMode::BvhVisits`.
+    pub max_visits: f64,
+}
+
+impl DebugIntegrator {
+    pub fn new(mode: DebugMode) -> Self {
+        Self {
+            mode,
+            max_distance: 1.0,
+            max_visits: 100.0,
+        }
+    }
+}
+
+impl Integrator for DebugIntegrator {
+    fn radiance(&self, ray: &Ray, world: &World, _sampler: &mut dyn Sampler) -> Vec3 {
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        if self.mode == DebugMode::BvhVisits {
+            let (_, visits) = bvh::count_node_visits(|| world.hit(ray, ray_t));
+            return heatmap(visits as f64 / self.max_visits);
+        }
+        let hit = world.hit(ray, ray_t);
+        let Some(rec) = hit else {
+            return match self.mode {
+                DebugMode::Albedo => world.background.value(ray.direction),
+                _ => Vec3::default(),
+            };
+        };
+
+        match self.mode {
+            DebugMode::Normals => {
+                let outward = if rec.front_face {
+                    rec.normal
+                } else {
+                    -rec.normal
+                };
+                (outward + Vec3::new(1.0, 1.0, 1.0)) * 0.5
+            }
+            DebugMode::Depth => {
+                let distance = rec.t * ray.direction.length() / self.max_distance;
+                Vec3::new(distance, distance, distance)
+            }
+            DebugMode::Uv => Vec3::new(rec.u, rec.v, 0.0),
+            DebugMode::Albedo => rec.material.albedo(&rec),
+            DebugMode::FrontFace if rec.front_face => Vec3::new(0.0, 1.0, 0.0),
+            DebugMode::FrontFace => Vec3::new(1.0, 0.0, 0.0),
+            DebugMode::BvhVisits => unreachable!(),
+        }
+    }
+}
+
+/// Maps [0, 1] through blue, cyan, green and yellow to red.
+fn heatmap(x: f64) -> Vec3 {
+    const STOPS: [Vec3; 5] = [
+        Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+        },
+        Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 1.0,
+        },
+        Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        Vec3 {
+            x: 1.0,
+            y: 1.0,
+            z: 0.0,
+        },
+        Vec3 {
+            x: 1.0,
+            y: 0.0,
+            z: 0.0,
+        },
+    ];
+    let scaled = x.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let i = (scaled as usize).min(STOPS.len() - 2);
+    let f = scaled - i as f64;
+    STOPS[i] * (1.0 - f) + STOPS[i + 1] * f
+}
@@ -0,0 +1,19 @@
+//! Light transport algorithms.
+//!
+//! The camera decides which rays to shoot and how to combine them into
+//! pixels; an `Integrator` decides how much light arrives along each ray.
+
+pub mod debug;
+pub mod simple_path;
+
+use crate::math::vec3::Vec3;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::scene::world::World;
+
+pub trait Integrator: Sync + Send {
+    /// Radiance arriving at the camera along `ray`. Every random decision
+    /// must come from `sampler`, which has already been started for the
+    /// current pixel sample.
+    fn radiance(&self, ray: &Ray, world: &World, sampler: &mut dyn Sampler) -> Vec3;
+}
@@ -0,0 +1,139 @@
+use crate::{
+    integrator::Integrator,
+    math::{interval::Interval, vec3::Vec3},
+    ray::Ray,
+    sampler::{MisHeuristic, Sampler},
+    scene::{hittable::HitRecord, world::World},
+};
+
+/// Unidirectional path tracer that follows one scattered ray per bounce
+/// until the path escapes, is absorbed, loses the Russian roulette or
+/// reaches `max_depth`.
+/// At every non-specular bounce it also samples the registered lights and
+/// combines both strategies with multiple importance sampling.
+pub struct SimplePathIntegrator {
+    /// Maximum number of bounces; longer paths contribute nothing.
+    pub max_depth: i32,
+    /// Bounces made before Russian roulette may end a path. Afterwards each
+    /// path continues with probability equal to its brightest throughput
+    /// channel.
+    pub rr_min_depth: i32,
+    /// How light and material samples share the direct lighting.
+    pub mis_heuristic: MisHeuristic,
+}
+
+impl SimplePathIntegrator {
+    pub fn new(max_depth: i32) -> Self {
+        Self {
+            max_depth,
+            rr_min_depth: 3,
+            mis_heuristic: MisHeuristic::default(),
+        }
+    }
+
+    /// Follows one path from the camera, accumulating emission and direct
+    /// lighting weighted by the path throughput so far.
+    ///
+    /// Between bounces, `bsdf_pdf` holds the density with which the last
+    /// material sample chose the current ray. It is `None` for the camera
+    /// ray and after specular bounces, whose emission is counted in full.
+    /// Otherwise the emission the ray finds is weighted against the chance
+    /// that light sampling at that bounce would have found it instead.
+    fn trace(&self, ray: &Ray, world: &World, sampler: &mut dyn Sampler) -> Vec3 {
+        let mut radiance = Vec3::default();
+        let mut throughput = Vec3::new(1.0, 1.0, 1.0);
+        let mut ray = *ray;
+        let mut bsdf_pdf = None;
+
+        for depth in 0..self.max_depth {
+            let Some(rec) = world.hit(&ray, Interval::new(0.001, f64::INFINITY)) else {
+                radiance = radiance + throughput * world.background.value(ray.direction);
+                break;
+            };
+
+            let mut emitted = rec.material.emitted(rec.u, rec.v, &rec.point);
+            if let Some(pdf) = bsdf_pdf {
+                let light_pdf = world.light_pdf(&ray, rec.t);
+                if light_pdf > 0.0 {
+                    emitted = emitted * self.mis_heuristic.weight(pdf, light_pdf);
+                }
+            }
+            radiance = radiance + throughput * emitted;
+
+            let scatter = rec.material.sample(&ray, &rec, sampler);
+            if !scatter.is_some_and(|s| s.is_specular) {
+                radiance = radiance + throughput * self.sample_lights(&ray, &rec, world, sampler);
+            }
+            let Some(scatter) = scatter else {
+                break;
+            };
+
+            throughput = throughput * scatter.attenuation;
+            ray = scatter.ray;
+            bsdf_pdf = (!scatter.is_specular).then_some(scatter.pdf);
+
+            // Russian roulette: end dim paths early, boosting the survivors
+            // so the estimate stays unbiased.
+            if depth + 1 >= self.rr_min_depth {
+                let survival = throughput.x.max(throughput.y).max(throughput.z).min(1.0);
+                if sampler.get_1d() >= survival {
+                    break;
+                }
+                throughput = throughput / survival;
+            }
+        }
+
+        radiance
+    }
+
+    /// Next-event estimation: one shadow ray towards a point on a randomly
+    /// chosen registered light, weighted against the chance that sampling
+    /// the material would have found the same point.
+    fn sample_lights(
+        &self,
+        r: &Ray,
+        rec: &HitRecord,
+        world: &World,
+        sampler: &mut dyn Sampler,
+    ) -> Vec3 {
+        if world.lights.is_empty() {
+            return Vec3::default();
+        }
+        let count = world.lights.len();
+        let index = ((sampler.get_1d() * count as f64) as usize).min(count - 1);
+        let Some(sample) = world.lights[index].sample(rec.point, r.time, sampler.get_2d()) else {
+            return Vec3::default();
+        };
+
+        let direction = sample.point - rec.point;
+        let f = rec.material.eval(r, rec, direction);
+        if f.is_near_zero() {
+            return Vec3::default();
+        }
+
+        let distance = direction.length();
+        let shadow_ray = Ray::new_with_time(rec.point, direction / distance, r.time);
+        let tolerance = 1e-4 * (1.0 + distance);
+        let Some(light_rec) = world
+            .hit(&shadow_ray, Interval::new(0.001, distance + tolerance))
+            .filter(|light_rec| light_rec.t > distance - tolerance)
+        else {
+            return Vec3::default();
+        };
+
+        let light_pdf = sample.pdf / count as f64;
+        let weight = self
+            .mis_heuristic
+            .weight(light_pdf, rec.material.pdf(r, rec, direction));
+        let emitted = light_rec
+            .material
+            .emitted(light_rec.u, light_rec.v, &light_rec.point);
+        f * emitted * (weight / light_pdf)
+    }
+}
+
+impl Integrator for SimplePathIntegrator {
+    fn radiance(&self, ray: &Ray, world: &World, sampler: &mut dyn Sampler) -> Vec3 {
+        self.trace(ray, world, sampler)
+    }
+}
@@ -1,6 +1,8 @@
 pub mod camera;
 pub mod geometry;
 pub mod image;
+pub mod integrator;
 pub mod math;
 pub mod ray;
+pub mod sampler;
 pub mod scene;
@@ -1,33 +1,385 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
+use image::ImageFormat;
+use raytracer::camera::aov::Aov;
+use raytracer::camera::projection::Projection;
+use raytracer::geometry::constant_medium::ConstantMedium;
+use raytracer::geometry::quad::{Quad, make_box};
 use raytracer::geometry::sphere::Sphere;
+use raytracer::geometry::transform::Transform;
 use raytracer::image::buffer::ImageBuffer;
+use raytracer::image::denoise::Denoiser;
+use raytracer::image::filter::Filter;
+use raytracer::image::filter::box_filter::BoxFilter;
+use raytracer::image::filter::gaussian::GaussianFilter;
+use raytracer::image::filter::lanczos::LanczosFilter;
+use raytracer::image::filter::mitchell::MitchellFilter;
+use raytracer::image::filter::tent::TentFilter;
+use raytracer::image::layers::{self, Layer};
+use raytracer::image::tonemap::ToneMap;
+use raytracer::integrator::Integrator;
+use raytracer::integrator::debug::{DebugIntegrator, DebugMode};
+use raytracer::integrator::simple_path::SimplePathIntegrator;
+use raytracer::math::mat4::Mat4;
+use raytracer::math::random::Rng;
 use raytracer::math::vec3::Vec3;
+use raytracer::scene::background::environment::EnvironmentMap;
+use raytracer::scene::background::solid::SolidBackground;
 use raytracer::scene::bvh::BvhNode;
+use raytracer::scene::hittable::Hittable;
 use raytracer::scene::hittable_list::HittableList;
 
 use raytracer::scene::material::Material;
 use raytracer::scene::material::dielectric::Dielectric;
+use raytracer::scene::material::diffuse_light::DiffuseLight;
 use raytracer::scene::texture::checkered::CheckerTexture;
 use raytracer::scene::texture::image_texture::ImageTexture;
+use raytracer::scene::world::World;
 use raytracer::{
     camera::Camera,
     scene::material::{lambertian::Lambertian, metal::Metal},
 };
 
+/// A camera and world ready to render, with the bounce limit suited to it.
+struct Scene {
+    camera: Camera,
+    world: World,
+    max_depth: i32,
+}
+
+/// Usage: `raytracer [scene] [--debug normals|depth|uv|albedo|front_face|bvh]
+/// [--adaptive <threshold>] [--progressive <samples per pass>] [--time-budget <seconds>]
+/// [--filter box|tent|gaussian|mitchell|lanczos] [--filter-radius <pixels>]
+/// [--projection perspective|orthographic|fisheye|equirectangular|cubemap]
+/// [--tonemap clip|reinhard|extended_reinhard|aces|agx] [--exposure-stops <stops>]
+/// [--aovs all|<name>,...] [--aov-files] [--denoise] [--output <path>]`
+///
+/// The image is written to `--output`, by default `images/<scene>.png`, in the
+/// format its extension names: `.png`, `.jpg`, `.tga` or `.ppm`, or, keeping
+/// the full linear radiance, `.exr`, `.hdr` or `.pfm`. The 8-bit formats are
+/// scaled by `--exposure-stops`, tone mapped (by default clipped) and
+/// sRGB-encoded; the float formats ignore both options. Both apply after a
+/// scene's physical camera exposure, which scales every format.
+///
+/// With `--adaptive`, pixels stop sampling once their relative error drops
+/// below the threshold, and the samples each took are written alongside the
+/// render with a `_samples` suffix, scaled so the sample limit is white.
+///
+/// `--progressive` and `--time-budget` render in passes over the whole image,
+/// rewriting the output after each pass, until the scene's sample count is
+/// reached or the budget runs out. Every pixel takes the same samples, so
+/// they cannot be combined with `--adaptive`.
+///
+/// `--aovs` renders extra buffers alongside the image: `depth`, `position`,
+/// `normal`, `albedo`, `object_id`, `material_id` and `motion`. With an `.exr`
+/// output they become layers of the same file; otherwise, or with
+/// `--aov-files`, each is written next to it with an `_<name>` suffix. These
+/// files keep the raw values: they are `.pfm` next to a `.pfm` output and
+/// `.exr` next to anything else.
+///
+/// `--denoise` filters the image, guided by albedo and normal buffers
+/// rendered alongside it; it cannot be combined with progressive rendering.
+///
+/// Other projections keep the scene's framing: orthographic covers what the
+/// perspective view shows at the `lookat` distance, fisheye covers 180°, and
+/// the panoramas switch to their usual 2:1 and 6:1 aspect ratios.
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let mut scene_name = "output";
+    let mut debug_mode = None;
+    let mut adaptive_threshold = None;
+    let mut samples_per_pass = None;
+    let mut time_budget = None;
+    let mut filter_name = None;
+    let mut filter_radius = None;
+    let mut projection_name = None;
+    let mut tone_map = None;
+    let mut exposure_stops = None;
+    let mut aovs = vec![];
+    let mut aov_files = false;
+    let mut denoise = false;
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--debug" {
+            let name = args.next().map_or("", |x| x.as_str());
+            let Some(mode) = DebugMode::from_name(name) else {
+                eprintln!("unknown debug mode '{}'", name);
+                std::process::exit(2);
+            };
+            debug_mode = Some((name, mode));
+        } else if arg == "--adaptive" {
+            adaptive_threshold = Some(parse_value::<f64>(arg, args.next()));
+        } else if arg == "--progressive" {
+            samples_per_pass = Some(parse_value::<i32>(arg, args.next()));
+        } else if arg == "--time-budget" {
+            let seconds = parse_value::<f64>(arg, args.next());
+            time_budget = Some(Duration::from_secs_f64(seconds.max(0.0)));
+        } else if arg == "--filter" {
+            filter_name = Some(parse_value::<String>(arg, args.next()));
+        } else if arg == "--filter-radius" {
+            filter_radius = Some(parse_value::<f64>(arg, args.next()));
+        } else if arg == "--projection" {
+            projection_name = Some(parse_value::<String>(arg, args.next()));
+        } else if arg == "--tonemap" {
+            let name = args.next().map_or("", |x| x.as_str());
+            let Some(operator) = ToneMap::from_name(name) else {
+                eprintln!("unknown tone mapping operator '{}'", name);
+                std::process::exit(2);
+            };
+            tone_map = Some(operator);
+        } else if arg == "--exposure-stops" {
+            exposure_stops = Some(parse_value::<f64>(arg, args.next()));
+        } else if arg == "--aovs" {
+            let names = args.next().map_or("", |x| x.as_str());
+            if names == "all" {
+                aovs = Aov::ALL.to_vec();
+                continue;
+            }
+            for name in names.split(',') {
+                let Some(aov) = Aov::from_name(name) else {
+                    eprintln!("unknown AOV '{}'", name);
+                    std::process::exit(2);
+                };
+                aovs.push(aov);
+            }
+        } else if arg == "--aov-files" {
+            aov_files = true;
+        } else if arg == "--denoise" {
+            denoise = true;
+        } else if arg == "--output" {
+            output = Some(parse_value::<PathBuf>(arg, args.next()));
+        } else {
+            scene_name = arg;
+        }
+    }
+
+    let mut scene = match scene_name {
+        "bouncing_spheres" => bouncing_spheres(),
+        "earth" => earth(),
+        "quads" => quads(),
+        "simple_light" => simple_light(),
+        "cornell_box" => cornell_box(),
+        "cornell_smoke" => cornell_smoke(),
+        "environment" => environment(),
+        _ => checkered_spheres(),
+    };
+
+    let (file_name, integrator): (String, Box<dyn Integrator>) = match debug_mode {
+        Some((name, mode)) => {
+            let mut integrator = DebugIntegrator::new(mode);
+            integrator.max_distance = farthest_distance(&scene);
+            (format!("{}_{}", scene_name, name), Box::new(integrator))
+        }
+        None => (
+            scene_name.to_string(),
+            Box::new(SimplePathIntegrator::new(scene.max_depth)),
+        ),
+    };
+    if filter_name.is_some() || filter_radius.is_some() {
+        let name = filter_name.as_deref().unwrap_or("box");
+        let Some(filter) = make_filter(name, filter_radius) else {
+            eprintln!("unknown filter '{}'", name);
+            std::process::exit(2);
+        };
+        scene.camera.filter = filter;
+    }
+    if let Some(name) = projection_name {
+        let camera = &mut scene.camera;
+        camera.projection = match name.as_str() {
+            "perspective" => Projection::Perspective,
+            "orthographic" => {
+                let distance = (camera.lookat - camera.lookfrom).length();
+                let half_angle = camera.vfov.to_radians() / 2.0;
+                Projection::Orthographic {
+                    height: 2.0 * half_angle.tan() * distance,
+                }
+            }
+            "fisheye" => Projection::Fisheye { fov: 180.0 },
+            "equirectangular" => {
+                camera.aspect_ratio = 2.0;
+                Projection::Equirectangular
+            }
+            "cubemap" => {
+                camera.aspect_ratio = 6.0;
+                Projection::Cubemap
+            }
+            _ => {
+                eprintln!("unknown projection '{}'", name);
+                std::process::exit(2);
+            }
+        };
+    }
+    if let Some(operator) = tone_map {
+        scene.camera.output.tone_map = operator;
+    }
+    if let Some(stops) = exposure_stops {
+        scene.camera.output.exposure_stops = stops;
+    }
+    if let Some(threshold) = adaptive_threshold {
+        if samples_per_pass.is_some() || time_budget.is_some() {
+            eprintln!("--adaptive cannot be used with progressive rendering");
+            std::process::exit(2);
+        }
+        scene.camera.adaptive_threshold = threshold;
+    }
+    scene.camera.aovs = aovs.clone();
+    if denoise {
+        if samples_per_pass.is_some() || time_budget.is_some() {
+            eprintln!("--denoise cannot be used with progressive rendering");
+            std::process::exit(2);
+        }
+        for guide in [Aov::Albedo, Aov::Normal] {
+            if !aovs.contains(&guide) {
+                scene.camera.aovs.push(guide);
+            }
+        }
+    }
+    let path = output.unwrap_or_else(|| PathBuf::from(format!("images/{}.png", file_name)));
+    if !ImageBuffer::can_save(&path) {
+        eprintln!("cannot write {}: unknown image format", path.display());
+        std::process::exit(2);
+    }
+    let img = if samples_per_pass.is_some() || time_budget.is_some() {
+        let camera = &mut scene.camera;
+        camera.samples_per_pass = samples_per_pass.unwrap_or(camera.samples_per_pass);
+        camera.time_budget = time_budget;
+        camera.snapshot_path = Some(path.clone());
+        camera.render_progressive(&scene.world, integrator.as_ref())
+    } else {
+        scene.camera.render(&scene.world, integrator.as_ref())
+    };
+
+    let camera = &scene.camera;
+    let img = match (camera.aov_layer(Aov::Albedo), camera.aov_layer(Aov::Normal)) {
+        (Some(albedo), Some(normal)) if denoise => Denoiser::new().denoise(&img, albedo, normal),
+        _ => img,
+    };
+
+    // the guides `--denoise` added are not written unless asked for
+    let layers: Vec<&Layer> = camera
+        .aov_layers()
+        .iter()
+        .filter(|layer| aovs.iter().any(|aov| aov.name() == layer.name))
+        .collect();
+    if layers.is_empty() {
+        save(&img, &path);
+    } else if !aov_files && ImageFormat::from_path(&path).is_ok_and(|f| f == ImageFormat::OpenExr) {
+        if let Err(err) = layers::write_exr(&path, &img, &layers) {
+            eprintln!("\nfailed to write {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    } else {
+        save(&img, &path);
+        let extension = if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("pfm"))
+        {
+            "pfm"
+        } else {
+            "exr"
+        };
+        for layer in layers {
+            let layer_path = with_suffix(&path, &format!("_{}", layer.name));
+            save(&layer.image, &layer_path.with_extension(extension));
+        }
+    }
+    print!("\rRendered {}!                        \n", path.display());
+
+    if adaptive_threshold.is_some() {
+        let scale = 1.0 / scene.camera.samples_per_pixel.max(1) as f64;
+        let mut counts = ImageBuffer::new(img.width, img.height);
+        for (count, &samples) in counts
+            .pixels
+            .iter_mut()
+            .zip(&scene.camera.sample_counts().pixels)
+        {
+            *count = samples * scale;
+        }
+        save(&counts, &with_suffix(&path, "_samples"));
+    }
+}
+
+/// The named reconstruction filter, with its usual radius unless one is given.
+fn make_filter(name: &str, radius: Option<f64>) -> Option<Arc<dyn Filter>> {
+    let filter: Arc<dyn Filter> = match name {
+        "box" => Arc::new(BoxFilter::new(radius.unwrap_or(0.5))),
+        "tent" => Arc::new(TentFilter::new(radius.unwrap_or(1.0))),
+        "gaussian" => Arc::new(GaussianFilter::new(radius.unwrap_or(1.5), 0.5)),
+        "mitchell" => Arc::new(MitchellFilter::new(
+            radius.unwrap_or(2.0),
+            1.0 / 3.0,
+            1.0 / 3.0,
+        )),
+        "lanczos" => Arc::new(LanczosFilter::new(radius.unwrap_or(3.0), 3.0)),
+        _ => return None,
+    };
+    Some(filter)
+}
+
+/// Writes `img` to `path`, exiting with a message on failure.
+fn save(img: &ImageBuffer, path: &Path) {
+    if let Err(err) = img.save(path) {
+        eprintln!("\nfailed to write {}: {}", path.display(), err);
+        std::process::exit(1);
+    }
+}
 
-    let scene_name = args.get(1).map(|x| x.as_str()).unwrap_or("output");
+/// `path` with `suffix` added to the file name, before the extension.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut name = format!("{}{}", stem, suffix);
+    if let Some(extension) = path.extension() {
+        name = format!("{}.{}", name, extension.to_string_lossy());
+    }
+    path.with_file_name(name)
+}
+
+/// Parses the value following `flag`, exiting with a message if it is
+/// missing or malformed.
+fn parse_value<T: FromStr>(flag: &str, value: Option<&String>) -> T {
+    let value = value.map_or("", |x| x.as_str());
+    match value.parse() {
+        Ok(parsed) => parsed,
+        Err(_) => {
+            eprintln!("invalid value '{}' for {}", value, flag);
+            std::process::exit(2);
+        }
+    }
+}
 
-    let img = checkered_spheres();
-    let path = format!("images/{}.ppm", scene_name);
-    img.write_ppm(path);
-    print!("\rRendered {}.ppm!                        \n", scene_name);
+/// Distance from the camera to the farthest corner of the scene's bounds,
+/// used to normalise depth images.
+fn farthest_distance(scene: &Scene) -> f64 {
+    let bbox = scene.world.objects.bounding_box();
+    let (x, y, z) = (
+        bbox.axis_interval(0),
+        bbox.axis_interval(1),
+        bbox.axis_interval(2),
+    );
+    let mut farthest: f64 = 0.0;
+    for corner in [
+        Vec3::new(x.min, y.min, z.min),
+        Vec3::new(x.min, y.min, z.max),
+        Vec3::new(x.min, y.max, z.min),
+        Vec3::new(x.min, y.max, z.max),
+        Vec3::new(x.max, y.min, z.min),
+        Vec3::new(x.max, y.min, z.max),
+        Vec3::new(x.max, y.max, z.min),
+        Vec3::new(x.max, y.max, z.max),
+    ] {
+        farthest = farthest.max((corner - scene.camera.lookfrom).length());
+    }
+    farthest
 }
 
-fn bouncing_spheres() -> ImageBuffer {
+fn bouncing_spheres() -> Scene {
     let mut world = HittableList::new();
+    let mut rng = Rng::new(0);
 
     let checker = Arc::new(CheckerTexture::from_colors(
         0.32,
@@ -44,27 +396,27 @@ fn bouncing_spheres() -> ImageBuffer {
 
     for i in -11..11 {
         for j in -11..11 {
-            let choose_material = rand::random_range(0.0..1.0);
+            let choose_material = rng.next_f64();
             let center = Vec3::new(
-                i as f64 + 0.9 * rand::random_range(0.0..1.0),
+                i as f64 + 0.9 * rng.next_f64(),
                 0.2,
-                j as f64 + 0.9 * rand::random_range(0.0..1.0),
+                j as f64 + 0.9 * rng.next_f64(),
             );
 
             if (center - Vec3::new(4.0, 0.2, 0.0)).length() > 0.9 {
                 let sphere_material: Arc<dyn Material>;
                 if choose_material < 0.8 {
-                    let albedo = Vec3::random() * Vec3::random();
+                    let albedo = Vec3::random(&mut rng) * Vec3::random(&mut rng);
                     sphere_material = Arc::new(Lambertian::from_color(albedo));
                     world.add(Arc::new(Sphere::moving(
                         center,
-                        center + Vec3::new(0.0, rand::random_range(0.0..0.5), 0.0),
+                        center + Vec3::new(0.0, rng.range(0.0, 0.5), 0.0),
                         0.2,
                         sphere_material,
                     )));
                 } else if choose_material < 0.95 {
-                    let albedo = Vec3::random_range(0.5, 1.0);
-                    let fuzz = rand::random_range(0.0..0.5);
+                    let albedo = Vec3::random_range(&mut rng, 0.5, 1.0);
+                    let fuzz = rng.range(0.0, 0.5);
                     sphere_material = Arc::new(Metal::new(albedo, fuzz));
                     world.add(Arc::new(Sphere::new(center, 0.2, sphere_material)));
                 } else {
@@ -95,13 +447,12 @@ fn bouncing_spheres() -> ImageBuffer {
         material3,
     )));
 
-    world = HittableList::from_object(Arc::new(BvhNode::new(world)));
+    let world = World::new(Arc::new(BvhNode::new(world)));
 
     let mut cam = Camera::new();
     cam.image_width = 400;
     cam.aspect_ratio = 16.0 / 9.0;
     cam.samples_per_pixel = 100;
-    cam.max_depth = 20;
     cam.vfov = 20.0;
     cam.vup = Vec3::new(0.0, 1.0, 0.0);
     cam.lookfrom = Vec3 {
@@ -116,10 +467,14 @@ fn bouncing_spheres() -> ImageBuffer {
     };
     cam.defocus_angle = 0.6;
     cam.focus_dist = 10.0;
-    cam.render(&world)
+    Scene {
+        camera: cam,
+        world,
+        max_depth: 20,
+    }
 }
 
-fn checkered_spheres() -> ImageBuffer {
+fn checkered_spheres() -> Scene {
     let mut world = HittableList::new();
 
     let checkered = Arc::new(CheckerTexture::from_colors(
@@ -139,13 +494,12 @@ fn checkered_spheres() -> ImageBuffer {
         Arc::new(Lambertian::from_texture(checkered.clone())),
     )));
 
-    world = HittableList::from_object(Arc::new(BvhNode::new(world)));
+    let world = World::new(Arc::new(BvhNode::new(world)));
 
     let mut cam = Camera::new();
     cam.aspect_ratio = 16.0 / 9.0;
     cam.image_width = 400;
     cam.samples_per_pixel = 100;
-    cam.max_depth = 50;
 
     cam.vfov = 20.0;
     cam.lookfrom = Vec3::new(13.0, 2.0, 3.0);
@@ -154,10 +508,14 @@ fn checkered_spheres() -> ImageBuffer {
 
     cam.defocus_angle = 0.0;
 
-    cam.render(&world)
+    Scene {
+        camera: cam,
+        world,
+        max_depth: 50,
+    }
 }
 
-fn earth() -> ImageBuffer {
+fn earth() -> Scene {
     let texture = Arc::new(ImageTexture::new("images/earthmap.jpg"));
     let surface = Arc::new(Lambertian::from_texture(texture));
     let globe = Arc::new(Sphere::new(Vec3::new(0.0, 0.0, 0.0), 2.0, surface));
@@ -166,7 +524,6 @@ fn earth() -> ImageBuffer {
     cam.aspect_ratio = 16.0 / 9.0;
     cam.image_width = 400;
     cam.samples_per_pixel = 100;
-    cam.max_depth = 50;
 
     cam.vfov = 20.0;
     cam.lookfrom = Vec3::new(0.0, 0.0, 12.0);
@@ -175,5 +532,291 @@ fn earth() -> ImageBuffer {
 
     cam.defocus_angle = 0.0;
 
-    cam.render(&HittableList::from_object(globe))
+    Scene {
+        camera: cam,
+        world: World::new(globe),
+        max_depth: 50,
+    }
+}
+
+fn quads() -> Scene {
+    let mut world = HittableList::new();
+
+    let left_red = Arc::new(Lambertian::from_color(Vec3::new(1.0, 0.2, 0.2)));
+    let back_green = Arc::new(Lambertian::from_color(Vec3::new(0.2, 1.0, 0.2)));
+    let right_blue = Arc::new(Lambertian::from_color(Vec3::new(0.2, 0.2, 1.0)));
+    let upper_orange = Arc::new(Lambertian::from_color(Vec3::new(1.0, 0.5, 0.0)));
+    let lower_teal = Arc::new(Lambertian::from_color(Vec3::new(0.2, 0.8, 0.8)));
+
+    world.add(Arc::new(Quad::new(
+        Vec3::new(-3.0, -2.0, 5.0),
+        Vec3::new(0.0, 0.0, -4.0),
+        Vec3::new(0.0, 4.0, 0.0),
+        left_red,
+    )));
+    world.add(Arc::new(Quad::new(
+        Vec3::new(-2.0, -2.0, 0.0),
+        Vec3::new(4.0, 0.0, 0.0),
+        Vec3::new(0.0, 4.0, 0.0),
+        back_green,
+    )));
+    world.add(Arc::new(Quad::new(
+        Vec3::new(3.0, -2.0, 1.0),
+        Vec3::new(0.0, 0.0, 4.0),
+        Vec3::new(0.0, 4.0, 0.0),
+        right_blue,
+    )));
+    world.add(Arc::new(Quad::new(
+        Vec3::new(-2.0, 3.0, 1.0),
+        Vec3::new(4.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 4.0),
+        upper_orange,
+    )));
+    world.add(Arc::new(Quad::new(
+        Vec3::new(-2.0, -3.0, 5.0),
+        Vec3::new(4.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -4.0),
+        lower_teal,
+    )));
+    let world = World::new(Arc::new(world));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 1.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 100;
+
+    cam.vfov = 80.0;
+    cam.lookfrom = Vec3::new(0.0, 0.0, 9.0);
+    cam.lookat = Vec3::new(0.0, 0.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+
+    Scene {
+        camera: cam,
+        world,
+        max_depth: 50,
+    }
+}
+
+fn simple_light() -> Scene {
+    let mut world = HittableList::new();
+
+    let ground = Arc::new(Lambertian::from_texture(Arc::new(
+        CheckerTexture::from_colors(0.5, Vec3::new(0.2, 0.3, 0.1), Vec3::new(0.9, 0.9, 0.9)),
+    )));
+    world.add(Arc::new(Sphere::new(
+        Vec3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground.clone(),
+    )));
+    world.add(Arc::new(Sphere::new(Vec3::new(0.0, 2.0, 0.0), 2.0, ground)));
+
+    let light = Arc::new(DiffuseLight::from_color(Vec3::new(4.0, 4.0, 4.0)));
+    let sphere_light: Arc<dyn Hittable> =
+        Arc::new(Sphere::new(Vec3::new(0.0, 7.0, 0.0), 2.0, light.clone()));
+    let quad_light: Arc<dyn Hittable> = Arc::new(Quad::new(
+        Vec3::new(3.0, 1.0, -2.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+        light,
+    ));
+    world.add(sphere_light.clone());
+    world.add(quad_light.clone());
+
+    let mut world = World::new(Arc::new(world));
+    world.add_light(sphere_light);
+    world.add_light(quad_light);
+    world.background = Arc::new(SolidBackground::black());
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 100;
+
+    cam.vfov = 20.0;
+    cam.lookfrom = Vec3::new(26.0, 3.0, 6.0);
+    cam.lookat = Vec3::new(0.0, 2.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+
+    Scene {
+        camera: cam,
+        world,
+        max_depth: 50,
+    }
+}
+
+/// The walls, ceiling light and two rotated boxes of the Cornell box. The
+/// light is already part of the returned list.
+fn cornell_box_world() -> (
+    HittableList,
+    Arc<dyn Hittable>,
+    Arc<Transform>,
+    Arc<Transform>,
+) {
+    let mut world = HittableList::new();
+
+    let red = Arc::new(Lambertian::from_color(Vec3::new(0.65, 0.05, 0.05)));
+    let white = Arc::new(Lambertian::from_color(Vec3::new(0.73, 0.73, 0.73)));
+    let green = Arc::new(Lambertian::from_color(Vec3::new(0.12, 0.45, 0.15)));
+    let light = Arc::new(DiffuseLight::from_color(Vec3::new(15.0, 15.0, 15.0)));
+
+    world.add(Arc::new(Quad::new(
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        green,
+    )));
+    world.add(Arc::new(Quad::new(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        red,
+    )));
+    let light: Arc<dyn Hittable> = Arc::new(Quad::new(
+        Vec3::new(343.0, 554.0, 332.0),
+        Vec3::new(-130.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -105.0),
+        light,
+    ));
+    world.add(light.clone());
+    world.add(Arc::new(Quad::new(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        white.clone(),
+    )));
+    world.add(Arc::new(Quad::new(
+        Vec3::new(555.0, 555.0, 555.0),
+        Vec3::new(-555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -555.0),
+        white.clone(),
+    )));
+    world.add(Arc::new(Quad::new(
+        Vec3::new(0.0, 0.0, 555.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        white.clone(),
+    )));
+
+    let box1 = Arc::new(make_box(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(165.0, 330.0, 165.0),
+        white.clone(),
+    ));
+    let box1 = Arc::new(Transform::new(
+        box1,
+        Mat4::translation(Vec3::new(265.0, 0.0, 295.0)) * Mat4::rotation_y(15.0),
+    ));
+
+    let box2 = Arc::new(make_box(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(165.0, 165.0, 165.0),
+        white,
+    ));
+    let box2 = Arc::new(Transform::new(
+        box2,
+        Mat4::translation(Vec3::new(130.0, 0.0, 65.0)) * Mat4::rotation_y(-18.0),
+    ));
+
+    (world, light, box1, box2)
+}
+
+fn cornell_camera() -> Camera {
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 1.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+
+    cam.vfov = 40.0;
+    cam.lookfrom = Vec3::new(278.0, 278.0, -800.0);
+    cam.lookat = Vec3::new(278.0, 278.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+    cam
+}
+
+fn cornell_box() -> Scene {
+    let (mut objects, light, box1, box2) = cornell_box_world();
+    objects.add(box1);
+    objects.add(box2);
+
+    let mut world = World::new(Arc::new(BvhNode::new(objects)));
+    world.add_light(light);
+    world.background = Arc::new(SolidBackground::black());
+    Scene {
+        camera: cornell_camera(),
+        world,
+        max_depth: 50,
+    }
+}
+
+fn cornell_smoke() -> Scene {
+    let (mut objects, light, box1, box2) = cornell_box_world();
+    objects.add(Arc::new(ConstantMedium::from_color(
+        box1,
+        0.01,
+        Vec3::new(0.0, 0.0, 0.0),
+    )));
+    objects.add(Arc::new(ConstantMedium::from_color(
+        box2,
+        0.01,
+        Vec3::new(1.0, 1.0, 1.0),
+    )));
+
+    let mut world = World::new(Arc::new(BvhNode::new(objects)));
+    world.add_light(light);
+    world.background = Arc::new(SolidBackground::black());
+    Scene {
+        camera: cornell_camera(),
+        world,
+        max_depth: 50,
+    }
+}
+
+fn environment() -> Scene {
+    let mut world = HittableList::new();
+
+    world.add(Arc::new(Sphere::new(
+        Vec3::new(-2.2, 0.0, 0.0),
+        1.0,
+        Arc::new(Metal::new(Vec3::new(0.9, 0.9, 0.9), 0.0)),
+    )));
+    world.add(Arc::new(Sphere::new(
+        Vec3::new(0.0, 0.0, 0.0),
+        1.0,
+        Arc::new(Dielectric::new(1.5)),
+    )));
+    world.add(Arc::new(Sphere::new(
+        Vec3::new(2.2, 0.0, 0.0),
+        1.0,
+        Arc::new(Lambertian::from_color(Vec3::new(0.8, 0.8, 0.8))),
+    )));
+
+    let mut sky =
+        EnvironmentMap::load("images/environment.hdr").expect("failed to load environment");
+    sky.rotation = 90.0;
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 100;
+
+    cam.vfov = 30.0;
+    cam.lookfrom = Vec3::new(0.0, 1.0, 10.0);
+    cam.lookat = Vec3::new(0.0, 0.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+
+    let mut world = World::new(Arc::new(world));
+    world.background = Arc::new(sky);
+    Scene {
+        camera: cam,
+        world,
+        max_depth: 50,
+    }
 }
@@ -1,3 +1,6 @@
 pub mod interval;
+pub mod mat4;
+pub mod onb;
+pub mod random;
 pub mod utils;
 pub mod vec3;
@@ -1,3 +1,5 @@
+use crate::math::random::Rng;
+
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
 pub struct Vec3 {
     pub x: f64,
@@ -40,25 +42,21 @@ impl Vec3 {
         }
     }
 
-    pub fn random() -> Vec3 {
-        Self::new(
-            rand::random_range(0.0..1.0),
-            rand::random_range(0.0..1.0),
-            rand::random_range(0.0..1.0),
-        )
+    pub fn random(rng: &mut Rng) -> Vec3 {
+        Self::new(rng.next_f64(), rng.next_f64(), rng.next_f64())
     }
 
-    pub fn random_range(min: f64, max: f64) -> Vec3 {
+    pub fn random_range(rng: &mut Rng, min: f64, max: f64) -> Vec3 {
         Self::new(
-            rand::random_range(min..max),
-            rand::random_range(min..max),
-            rand::random_range(min..max),
+            rng.range(min, max),
+            rng.range(min, max),
+            rng.range(min, max),
         )
     }
 
-    pub fn random_unit_vector() -> Vec3 {
+    pub fn random_unit_vector(rng: &mut Rng) -> Vec3 {
         loop {
-            let p = Self::random_range(-1.0, 1.0);
+            let p = Self::random_range(rng, -1.0, 1.0);
 
             if 1e-160 < p.length_squared() && p.length_squared() <= 1.0 {
                 return p.normalized();
@@ -66,8 +64,8 @@ impl Vec3 {
         }
     }
 
-    pub fn random_on_hemisphere(normal: Vec3) -> Vec3 {
-        let on_unit_sphere = Vec3::random_unit_vector();
+    pub fn random_on_hemisphere(rng: &mut Rng, normal: Vec3) -> Vec3 {
+        let on_unit_sphere = Vec3::random_unit_vector(rng);
         if on_unit_sphere.dot(normal) > 0.0 {
             on_unit_sphere
         } else {
@@ -75,19 +73,20 @@ impl Vec3 {
         }
     }
 
-    pub fn random_in_unit_disk() -> Vec3 {
+    pub fn random_in_unit_disk(rng: &mut Rng) -> Vec3 {
         loop {
-            let p = Vec3::new(
-                rand::random_range(-1.0..1.0),
-                rand::random_range(-1.0..1.0),
-                0.0,
-            );
+            let p = Vec3::new(rng.range(-1.0, 1.0), rng.range(-1.0, 1.0), 0.0);
             if p.length_squared() < 1.0 {
                 return p;
             }
         }
     }
 
+    /// Relative luminance of a linear Rec. 709 colour.
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.x + 0.7152 * self.y + 0.0722 * self.z
+    }
+
     pub fn is_near_zero(&self) -> bool {
         self.x.abs() < 1e-8 && self.y.abs() < 1e-8 && self.z.abs() < 1e-8
     }
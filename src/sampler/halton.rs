@@ -0,0 +1,84 @@
+use crate::math::random::Rng;
+use crate::sampler::{ONE_MINUS_EPSILON, Sampler, hash, permutation_element};
+
+/// Bases of the Halton dimensions; later dimensions fall back to random numbers.
+const PRIMES: [u64; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+/// The Halton sequence, dimension `d` being the radical inverse in the
+/// `d`-th prime base. Each pixel gets its own Owen scrambling of the digits,
+/// which keeps the sequence's stratification while decorrelating pixels.
+pub struct HaltonSampler {
+    seed: u64,
+    pixel: u64,
+    sample_index: u64,
+    dimension: usize,
+    rng: Rng,
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            pixel: 0,
+            sample_index: 0,
+            dimension: 0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    fn sample_dimension(&mut self) -> f64 {
+        let dimension = self.dimension;
+        self.dimension += 1;
+        match PRIMES.get(dimension) {
+            Some(&base) => {
+                let scramble = hash(&[self.seed, self.pixel, dimension as u64]);
+                owen_scrambled_radical_inverse(base, self.sample_index, scramble)
+            }
+            None => self.rng.next_f64(),
+        }
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, sample_index: usize) {
+        self.pixel = hash(&[x as u64, y as u64]);
+        self.sample_index = sample_index as u64;
+        self.dimension = 0;
+        self.rng = Rng::from_stream(hash(&[self.seed, sample_index as u64]), self.pixel);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        self.sample_dimension()
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        // keep both halves of a 2D sample in consecutive Halton bases
+        if self.dimension + 1 == PRIMES.len() {
+            self.dimension += 1;
+        }
+        (self.sample_dimension(), self.sample_dimension())
+    }
+}
+
+/// Radical inverse of `a` in `base`, with each digit permuted by a hash of
+/// the digits before it (a nested uniform, i.e. Owen, scramble).
+fn owen_scrambled_radical_inverse(base: u64, mut a: u64, scramble: u64) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut inv_base_m = 1.0;
+    let mut reversed_digits: u64 = 0;
+
+    // keep going past the last nonzero digit: scrambled zeros still matter
+    while 1.0 - (base - 1) as f64 * inv_base_m < 1.0 {
+        let next = a / base;
+        let digit = a - next * base;
+        let digit_seed = hash(&[scramble, reversed_digits]) as u32;
+        let digit = permutation_element(digit as u32, base as u32, digit_seed) as u64;
+        reversed_digits = reversed_digits * base + digit;
+        inv_base_m *= inv_base;
+        a = next;
+    }
+    (inv_base_m * reversed_digits as f64).min(ONE_MINUS_EPSILON)
+}
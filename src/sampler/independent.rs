@@ -0,0 +1,32 @@
+use crate::math::random::Rng;
+use crate::sampler::{Sampler, hash};
+
+/// Plain uniform random numbers, with a separate stream per pixel sample.
+pub struct IndependentSampler {
+    seed: u64,
+    rng: Rng,
+}
+
+impl IndependentSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, sample_index: usize) {
+        let pixel = hash(&[x as u64, y as u64]);
+        self.rng = Rng::from_stream(hash(&[self.seed, sample_index as u64]), pixel);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        self.rng.next_f64()
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        (self.rng.next_f64(), self.rng.next_f64())
+    }
+}
@@ -0,0 +1,152 @@
+//! Sample generators for the camera and materials.
+//!
+//! A `Sampler` hands out the random numbers of one camera sample as a
+//! sequence of 1D and 2D dimensions. Low-discrepancy samplers place the
+//! samples of a pixel so they cover each dimension more evenly than
+//! independent random numbers, which reduces noise at the same sample count.
+
+pub mod halton;
+pub mod independent;
+pub mod sobol;
+pub mod stratified;
+
+use std::f64::consts::PI;
+
+use crate::math::random::mix;
+use crate::math::vec3::Vec3;
+use crate::sampler::halton::HaltonSampler;
+use crate::sampler::independent::IndependentSampler;
+use crate::sampler::sobol::SobolSampler;
+use crate::sampler::stratified::StratifiedSampler;
+
+/// Largest `f64` below one; keeps `[0, 1)` samples from rounding up to 1.
+pub const ONE_MINUS_EPSILON: f64 = 1.0 - f64::EPSILON / 2.0;
+
+pub trait Sampler: Send {
+    /// Begins sample `sample_index` of pixel (`x`, `y`), resetting the
+    /// dimension counter.
+    fn start_pixel_sample(&mut self, x: usize, y: usize, sample_index: usize);
+
+    /// The next dimension, uniform in [0, 1).
+    fn get_1d(&mut self) -> f64;
+
+    /// The next two dimensions, uniform over [0, 1)^2.
+    fn get_2d(&mut self) -> (f64, f64);
+}
+
+/// Which `Sampler` a camera renders with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SamplerType {
+    /// Independent uniform random numbers.
+    #[default]
+    Independent,
+    /// Jittered strata, shuffled independently per dimension.
+    Stratified,
+    /// Owen-scrambled Halton sequence.
+    Halton,
+    /// Owen-scrambled Sobol (0,2)-sequence, padded across dimensions.
+    Sobol,
+}
+
+impl SamplerType {
+    /// Creates a sampler expecting `samples_per_pixel` samples in each pixel.
+    /// Every choice is derived from `seed`, so renders are reproducible.
+    pub fn build(self, samples_per_pixel: usize, seed: u64) -> Box<dyn Sampler> {
+        let spp = samples_per_pixel.max(1);
+        match self {
+            SamplerType::Independent => Box::new(IndependentSampler::new(seed)),
+            SamplerType::Stratified => Box::new(StratifiedSampler::new(spp, seed)),
+            SamplerType::Halton => Box::new(HaltonSampler::new(seed)),
+            SamplerType::Sobol => Box::new(SobolSampler::new(spp, seed)),
+        }
+    }
+}
+
+/// How multiple importance sampling splits a contribution between light
+/// sampling and material sampling.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MisHeuristic {
+    /// Weights proportional to each strategy's density.
+    Balance,
+    /// Weights proportional to each density squared (Veach's power
+    /// heuristic with beta = 2); usually the lower-variance choice.
+    #[default]
+    Power,
+}
+
+impl MisHeuristic {
+    /// Weight of a sample drawn with density `pdf` when the other strategy
+    /// would have produced the same sample with density `other_pdf`.
+    pub fn weight(self, pdf: f64, other_pdf: f64) -> f64 {
+        if pdf.is_infinite() {
+            return 1.0;
+        }
+        let (a, b) = match self {
+            MisHeuristic::Balance => (pdf, other_pdf),
+            MisHeuristic::Power => (pdf * pdf, other_pdf * other_pdf),
+        };
+        if a + b > 0.0 { a / (a + b) } else { 0.0 }
+    }
+}
+
+/// Hashes any number of integers into one well-mixed value.
+pub(crate) fn hash(values: &[u64]) -> u64 {
+    values.iter().fold(0, |h, &v| mix(h ^ v))
+}
+
+/// Element `i` of a pseudo-random permutation of `0..n` chosen by `seed`
+/// (Kensler, "Correlated Multi-Jittered Sampling").
+pub(crate) fn permutation_element(mut i: u32, n: u32, seed: u32) -> u32 {
+    let mut w = n.wrapping_sub(1);
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dcb303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e501cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < n {
+            return i.wrapping_add(seed) % n;
+        }
+    }
+}
+
+/// Maps a 2D sample to a uniformly distributed unit vector.
+pub fn sample_uniform_sphere(u: (f64, f64)) -> Vec3 {
+    let z = 1.0 - 2.0 * u.0;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * u.1;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+/// Maps a 2D sample to a uniformly distributed point in the unit disk
+/// (z = 0), using Shirley's concentric mapping to preserve stratification.
+pub fn sample_uniform_disk(u: (f64, f64)) -> Vec3 {
+    let (ox, oy) = (2.0 * u.0 - 1.0, 2.0 * u.1 - 1.0);
+    if ox == 0.0 && oy == 0.0 {
+        return Vec3::default();
+    }
+    let (r, theta) = if ox.abs() > oy.abs() {
+        (ox, PI / 4.0 * (oy / ox))
+    } else {
+        (oy, PI / 2.0 - PI / 4.0 * (ox / oy))
+    };
+    Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
+}
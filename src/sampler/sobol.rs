@@ -0,0 +1,95 @@
+use crate::sampler::{ONE_MINUS_EPSILON, Sampler, hash, permutation_element};
+
+/// The first two Sobol dimensions, reused for every pair of dimensions.
+///
+/// Each 1D or 2D dimension shuffles the pixel's sample indices with its own
+/// permutation and applies its own Owen scramble, so consecutive
+/// dimensions are decorrelated while each stays a well-stratified
+/// (0,2)-sequence. This is the "padded" construction used by pbrt.
+pub struct SobolSampler {
+    samples_per_pixel: u32,
+    seed: u64,
+    pixel: u64,
+    sample_index: u32,
+    dimension: u64,
+}
+
+impl SobolSampler {
+    pub fn new(samples_per_pixel: usize, seed: u64) -> Self {
+        Self {
+            samples_per_pixel: samples_per_pixel.min(u32::MAX as usize) as u32,
+            seed,
+            pixel: 0,
+            sample_index: 0,
+            dimension: 0,
+        }
+    }
+
+    /// The index into the sequence used for the current dimension, and the
+    /// seed for its scrambling.
+    fn next_index_and_seed(&mut self) -> (u32, u64) {
+        let h = hash(&[self.seed, self.pixel, self.dimension]);
+        self.dimension += 1;
+        let index = if self.sample_index < self.samples_per_pixel {
+            permutation_element(self.sample_index, self.samples_per_pixel, h as u32)
+        } else {
+            self.sample_index
+        };
+        (index, h)
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, sample_index: usize) {
+        self.pixel = hash(&[x as u64, y as u64]);
+        self.sample_index = sample_index as u32;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let (index, h) = self.next_index_and_seed();
+        to_unit(fast_owen_scramble(index.reverse_bits(), h as u32))
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let (index, h) = self.next_index_and_seed();
+        self.dimension += 1;
+        (
+            to_unit(fast_owen_scramble(index.reverse_bits(), h as u32)),
+            to_unit(fast_owen_scramble(
+                sobol_dimension_1(index),
+                (h >> 32) as u32,
+            )),
+        )
+    }
+}
+
+/// Second Sobol dimension, generated by the primitive polynomial x + 1.
+fn sobol_dimension_1(mut index: u32) -> u32 {
+    let mut v: u32 = 1 << 31;
+    let mut result = 0;
+    while index != 0 {
+        if index & 1 != 0 {
+            result ^= v;
+        }
+        v ^= v >> 1;
+        index >>= 1;
+    }
+    result
+}
+
+/// Hash-based Owen scrambling of a 32-bit fixed-point value (Laine and
+/// Karras, as refined by Burley and pbrt-v4).
+fn fast_owen_scramble(mut v: u32, seed: u32) -> u32 {
+    v = v.reverse_bits();
+    v ^= v.wrapping_mul(0x3d20adea);
+    v = v.wrapping_add(seed);
+    v = v.wrapping_mul((seed >> 16) | 1);
+    v ^= v.wrapping_mul(0x05526c56);
+    v ^= v.wrapping_mul(0x53a22864);
+    v.reverse_bits()
+}
+
+fn to_unit(v: u32) -> f64 {
+    (v as f64 / 4294967296.0).min(ONE_MINUS_EPSILON)
+}
@@ -0,0 +1,64 @@
+use crate::math::random::Rng;
+use crate::sampler::{ONE_MINUS_EPSILON, Sampler, hash, permutation_element};
+
+/// Jittered sampling: each dimension is split into one stratum per sample
+/// (a square grid for 2D dimensions) and every sample lands in a different
+/// stratum. Strata are assigned through a per-pixel, per-dimension
+/// permutation so dimensions stay uncorrelated.
+pub struct StratifiedSampler {
+    samples_per_pixel: usize,
+    grid: usize,
+    seed: u64,
+    pixel: u64,
+    sample_index: usize,
+    dimension: u64,
+    rng: Rng,
+}
+
+impl StratifiedSampler {
+    pub fn new(samples_per_pixel: usize, seed: u64) -> Self {
+        Self {
+            samples_per_pixel,
+            grid: (samples_per_pixel as f64).sqrt().ceil() as usize,
+            seed,
+            pixel: 0,
+            sample_index: 0,
+            dimension: 0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// The stratum this sample occupies in the current dimension, out of `n`.
+    fn stratum(&self, n: usize) -> usize {
+        let index = self.sample_index % n;
+        let permutation_seed = hash(&[self.seed, self.pixel, self.dimension]) as u32;
+        permutation_element(index as u32, n as u32, permutation_seed) as usize
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, sample_index: usize) {
+        self.pixel = hash(&[x as u64, y as u64]);
+        self.sample_index = sample_index;
+        self.dimension = 0;
+        self.rng = Rng::from_stream(hash(&[self.seed, sample_index as u64]), self.pixel);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let n = self.samples_per_pixel;
+        let stratum = self.stratum(n);
+        self.dimension += 1;
+        ((stratum as f64 + self.rng.next_f64()) / n as f64).min(ONE_MINUS_EPSILON)
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let n = self.grid;
+        let stratum = self.stratum(n * n);
+        self.dimension += 2;
+        let (x, y) = (stratum % n, stratum / n);
+        (
+            ((x as f64 + self.rng.next_f64()) / n as f64).min(ONE_MINUS_EPSILON),
+            ((y as f64 + self.rng.next_f64()) / n as f64).min(ONE_MINUS_EPSILON),
+        )
+    }
+}
@@ -12,7 +12,7 @@ pub struct Aabb {
 
 impl Aabb {
     pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
-        Self { x, y, z }
+        Self { x, y, z }.padded()
     }
 
     pub fn from_extrema(a: Vec3, b: Vec3) -> Self {
@@ -21,6 +21,19 @@ impl Aabb {
             y: Interval::new(a.y.min(b.y), a.y.max(b.y)),
             z: Interval::new(a.z.min(b.z), a.z.max(b.z)),
         }
+        .padded()
+    }
+
+    /// Widens any axis thinner than a small delta so flat primitives (quads,
+    /// axis-aligned triangles) still produce a box the slab test can hit.
+    fn padded(self) -> Self {
+        const DELTA: f64 = 0.0001;
+        let pad = |i: Interval| if i.size() < DELTA { i.expand(DELTA) } else { i };
+        Self {
+            x: pad(self.x),
+            y: pad(self.y),
+            z: pad(self.z),
+        }
     }
 
     pub fn enclosing(box1: Self, box2: Self) -> Self {
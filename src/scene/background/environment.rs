@@ -0,0 +1,71 @@
+use std::f64::consts::PI;
+use std::path::Path;
+
+use crate::math::utils::degree_to_radians;
+use crate::{math::vec3::Vec3, scene::background::Background};
+
+/// Equirectangular (latitude-longitude) environment map.
+///
+/// Pixels are used as linear radiance, as stored in HDR formats such as
+/// Radiance `.hdr` or OpenEXR; no sRGB decoding is applied.
+pub struct EnvironmentMap {
+    pixels: Vec<Vec3>,
+    width: usize,
+    height: usize,
+    /// Rotation about the world up (+y) axis, in degrees.
+    pub rotation: f64,
+    /// Scale applied to every radiance value.
+    pub intensity: f64,
+}
+
+impl EnvironmentMap {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_rgb32f();
+        let (w, h) = image.dimensions();
+        Ok(Self {
+            width: w as usize,
+            height: h as usize,
+            pixels: image
+                .pixels()
+                .map(|p| Vec3::new(p[0] as f64, p[1] as f64, p[2] as f64))
+                .collect(),
+            rotation: 0.0,
+            intensity: 1.0,
+        })
+    }
+
+    fn texel(&self, x: usize, y: usize) -> Vec3 {
+        self.pixels[y * self.width + x]
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn value(&self, direction: Vec3) -> Vec3 {
+        if self.width == 0 || self.height == 0 {
+            return Vec3::default();
+        }
+        let d = direction.normalized();
+
+        // same parameterisation as Sphere::get_uv, turned by `rotation`
+        let phi = f64::atan2(-d.z, d.x) + PI + degree_to_radians(self.rotation);
+        let theta = f64::acos((-d.y).clamp(-1.0, 1.0));
+        let u = (phi / (2.0 * PI)).rem_euclid(1.0);
+        let v = 1.0 - theta / PI;
+
+        // bilinear filtering, wrapping horizontally and clamping at the poles
+        let x = u * self.width as f64 - 0.5;
+        let y = (v * self.height as f64 - 0.5).clamp(0.0, (self.height - 1) as f64);
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let (fx, fy) = (x - x0, y - y0);
+
+        let x0 = (x0 as i64).rem_euclid(self.width as i64) as usize;
+        let x1 = (x0 + 1) % self.width;
+        let y0 = y0 as usize;
+        let y1 = (y0 + 1).min(self.height - 1);
+
+        let top = self.texel(x0, y0) * (1.0 - fx) + self.texel(x1, y0) * fx;
+        let bottom = self.texel(x0, y1) * (1.0 - fx) + self.texel(x1, y1) * fx;
+        (top * (1.0 - fy) + bottom * fy) * self.intensity
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{math::vec3::Vec3, scene::background::Background};
+
+/// Vertical blend from `bottom` (looking straight down) to `top` (straight up).
+#[derive(Clone, Copy, Debug)]
+pub struct GradientBackground {
+    bottom: Vec3,
+    top: Vec3,
+}
+
+impl GradientBackground {
+    pub fn new(bottom: Vec3, top: Vec3) -> Self {
+        Self { bottom, top }
+    }
+
+    /// The white-to-blue sky used when nothing else is configured.
+    pub fn sky() -> Self {
+        Self::new(Vec3::new(1.0, 1.0, 1.0), Vec3::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Background for GradientBackground {
+    fn value(&self, direction: Vec3) -> Vec3 {
+        let unit = direction.normalized();
+        let t = 0.5 * (unit.y + 1.0);
+        self.bottom * (1.0 - t) + self.top * t
+    }
+}
@@ -0,0 +1,10 @@
+use crate::math::vec3::Vec3;
+
+pub mod environment;
+pub mod gradient;
+pub mod solid;
+
+/// Radiance arriving from infinitely far away, seen by rays that escape the scene.
+pub trait Background: Sync + Send {
+    fn value(&self, direction: Vec3) -> Vec3;
+}
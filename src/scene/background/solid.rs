@@ -0,0 +1,21 @@
+use crate::{math::vec3::Vec3, scene::background::Background};
+
+#[derive(Clone, Copy, Debug)]
+pub struct SolidBackground {
+    color: Vec3,
+}
+
+impl SolidBackground {
+    pub fn new(color: Vec3) -> Self {
+        Self { color }
+    }
+    pub fn black() -> Self {
+        Self::new(Vec3::default())
+    }
+}
+
+impl Background for SolidBackground {
+    fn value(&self, _direction: Vec3) -> Vec3 {
+        self.color
+    }
+}
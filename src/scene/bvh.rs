@@ -1,4 +1,11 @@
-use std::{cmp::Ordering, sync::Arc};
+use std::{
+    cell::Cell,
+    cmp::Ordering,
+    sync::{
+        Arc,
+        atomic::{self, AtomicUsize},
+    },
+};
 
 use crate::{
     math::interval::Interval,
@@ -10,19 +17,53 @@ use crate::{
     },
 };
 
+thread_local! {
+    static NODE_VISITS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Number of `count_node_visits` calls in progress on any thread. Nodes
+/// only touch the thread-local counter while it is non-zero, so ordinary
+/// renders skip it.
+static COUNTING: AtomicUsize = AtomicUsize::new(0);
+
+/// Runs `f`, returning its result and the number of `BvhNode`s whose
+/// bounding box this thread tested meanwhile.
+pub fn count_node_visits<T>(f: impl FnOnce() -> T) -> (T, u64) {
+    COUNTING.fetch_add(1, atomic::Ordering::Relaxed);
+    NODE_VISITS.with(|visits| visits.set(0));
+    let result = f();
+    let visits = NODE_VISITS.with(Cell::get);
+    COUNTING.fetch_sub(1, atomic::Ordering::Relaxed);
+    (result, visits)
+}
+
 pub struct BvhNode {
     left: Arc<dyn Hittable>,
     right: Arc<dyn Hittable>,
+    /// Object IDs given to hits on `left` and `right` when they are
+    /// objects of the list passed to `new`; 0 keeps the hit's own ID.
+    ids: [u32; 2],
     bbox: Aabb,
 }
 
 impl BvhNode {
-    pub fn new(mut list: HittableList) -> Self {
-        Self::from_objects(&mut list.objects)
+    /// Builds a hierarchy over the objects of `list`, giving their hits the
+    /// object IDs a `HittableList` would: their 1-based positions in it.
+    pub fn new(list: HittableList) -> Self {
+        let mut objects: Vec<(Arc<dyn Hittable>, u32)> =
+            list.objects.into_iter().zip(1..).collect();
+        Self::build(&mut objects)
     }
+
     pub fn from_objects(objects: &mut [Arc<dyn Hittable>]) -> Self {
+        let mut objects: Vec<(Arc<dyn Hittable>, u32)> =
+            objects.iter().map(|object| (object.clone(), 0)).collect();
+        Self::build(&mut objects)
+    }
+
+    fn build(objects: &mut [(Arc<dyn Hittable>, u32)]) -> Self {
         let mut bbox = Aabb::empty();
-        for obj in objects.iter() {
+        for (obj, _) in objects.iter() {
             bbox = Aabb::enclosing(bbox, obj.bounding_box());
         }
         let axis = bbox.longer_axis();
@@ -37,24 +78,33 @@ impl BvhNode {
 
         let left;
         let right;
+        let ids;
 
         match object_span {
             1 => {
-                left = objects[0].clone();
-                right = objects[0].clone();
+                left = objects[0].0.clone();
+                right = objects[0].0.clone();
+                ids = [objects[0].1; 2];
             }
             2 => {
-                left = objects[0].clone();
-                right = objects[1].clone();
+                left = objects[0].0.clone();
+                right = objects[1].0.clone();
+                ids = [objects[0].1, objects[1].1];
             }
             _ => {
-                objects.sort_unstable_by(comparator);
+                objects.sort_unstable_by(|a, b| comparator(&a.0, &b.0));
                 let mid = object_span / 2;
-                left = Arc::new(BvhNode::from_objects(&mut objects[0..mid]));
-                right = Arc::new(BvhNode::from_objects(&mut objects[mid..]));
+                left = Arc::new(BvhNode::build(&mut objects[0..mid]));
+                right = Arc::new(BvhNode::build(&mut objects[mid..]));
+                ids = [0; 2];
             }
         }
-        Self { left, right, bbox }
+        Self {
+            left,
+            right,
+            ids,
+            bbox,
+        }
     }
 
     pub fn box_compare(a: &Arc<dyn Hittable>, b: &Arc<dyn Hittable>, axis_index: i32) -> Ordering {
@@ -79,6 +129,9 @@ impl BvhNode {
 
 impl Hittable for BvhNode {
     fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        if COUNTING.load(atomic::Ordering::Relaxed) > 0 {
+            NODE_VISITS.with(|visits| visits.set(visits.get() + 1));
+        }
         if !self.bbox.hit(ray, ray_t) {
             return None;
         }
@@ -89,7 +142,15 @@ impl Hittable for BvhNode {
         };
         let hit_right = self.right.hit(ray, Interval::new(ray_t.min, t_max));
 
-        hit_right.or(hit_left)
+        let (mut rec, id) = match (hit_right, hit_left) {
+            (Some(rec), _) => (rec, self.ids[1]),
+            (None, Some(rec)) => (rec, self.ids[0]),
+            (None, None) => return None,
+        };
+        if id != 0 {
+            rec.object_id = id;
+        }
+        Some(rec)
     }
 
     fn bounding_box(&self) -> Aabb {
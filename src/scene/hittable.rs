@@ -17,6 +17,11 @@ pub struct HitRecord {
     pub material: Arc<dyn Material>,
     pub u: f64,
     pub v: f64,
+    /// Velocity of the surface at `point`, in world units per unit of time.
+    pub velocity: Vec3,
+    /// 1-based position of the hit object in the outermost `HittableList`
+    /// or `BvhNode::new` list that contains it, or 0 if there is none.
+    pub object_id: u32,
 }
 
 impl HitRecord {
@@ -43,6 +48,8 @@ impl HitRecord {
             material,
             u,
             v,
+            velocity: Vec3::default(),
+            object_id: 0,
         }
     }
 }
@@ -57,11 +64,34 @@ impl Default for HitRecord {
             material: Arc::new(Lambertian::from_color(Vec3::default())), // placeholder
             u: Default::default(),
             v: Default::default(),
+            velocity: Vec3::default(),
+            object_id: 0,
         }
     }
 }
 
-pub trait Hittable {
+/// A point chosen on a surface for direct light sampling.
+#[derive(Clone, Copy, Debug)]
+pub struct LightSample {
+    pub point: Vec3,
+    /// Density of choosing `point`, per unit solid angle as seen from the origin.
+    pub pdf: f64,
+}
+
+pub trait Hittable: Sync + Send {
     fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord>;
     fn bounding_box(&self) -> Aabb;
+
+    /// Picks a point on the surface visible from `origin` at `time`, driven
+    /// by the 2D sample `u`. Shapes that cannot be used as area lights
+    /// return `None`.
+    fn sample(&self, _origin: Vec3, _time: f64, _u: (f64, f64)) -> Option<LightSample> {
+        None
+    }
+
+    /// Solid-angle density with which `sample` would pick the point seen
+    /// from `origin` along `direction` at `time`; zero if the ray misses.
+    fn pdf_value(&self, _origin: Vec3, _direction: Vec3, _time: f64) -> f64 {
+        0.0
+    }
 }
@@ -31,14 +31,21 @@ impl HittableList {
     }
 }
 
+impl Default for HittableList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Hittable for HittableList {
     fn hit(&self, r: &Ray, ray_t: Interval) -> Option<HitRecord> {
         let mut closest_hit = None;
         let mut closest = ray_t.max;
 
-        for obj in &self.objects {
-            if let Some(hit) = obj.hit(r, Interval::new(ray_t.min, closest)) {
+        for (i, obj) in self.objects.iter().enumerate() {
+            if let Some(mut hit) = obj.hit(r, Interval::new(ray_t.min, closest)) {
                 closest = hit.t;
+                hit.object_id = i as u32 + 1;
                 closest_hit = Some(hit)
             }
         }
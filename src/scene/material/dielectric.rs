@@ -1,4 +1,12 @@
-use crate::{math::vec3::Vec3, ray::Ray, scene::material::Material};
+use crate::{
+    math::vec3::Vec3,
+    ray::Ray,
+    sampler::Sampler,
+    scene::{
+        hittable::HitRecord,
+        material::{Material, ScatterRecord},
+    },
+};
 
 pub struct Dielectric {
     refractive_index: f64,
@@ -16,11 +24,12 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(
+    fn sample(
         &self,
-        ray_in: &crate::ray::Ray,
-        rec: &crate::scene::hittable::HitRecord,
-    ) -> Option<(Vec3, Ray)> {
+        ray_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
         let ri = if rec.front_face {
             1.0 / self.refractive_index
         } else {
@@ -29,16 +38,21 @@ impl Material for Dielectric {
         let unit_direction = ray_in.direction.normalized();
         let cos_theta = f64::min((-unit_direction).dot(rec.normal), 1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
-        let direction;
-        if ri * sin_theta > 1.0 || (Self::reflectance(cos_theta, ri) > rand::random_range(0.0..1.0))
-        {
-            direction = unit_direction.reflect(rec.normal);
-        } else {
-            direction = unit_direction.refract(rec.normal, ri);
-        }
-        Some((
-            Vec3::new(1.0, 1.0, 1.0),
-            Ray::new_with_time(rec.point, direction, ray_in.time),
-        ))
+        let direction =
+            if ri * sin_theta > 1.0 || (Self::reflectance(cos_theta, ri) > sampler.get_1d()) {
+                unit_direction.reflect(rec.normal)
+            } else {
+                unit_direction.refract(rec.normal, ri)
+            };
+        Some(ScatterRecord {
+            attenuation: Vec3::new(1.0, 1.0, 1.0),
+            ray: Ray::new_with_time(rec.point, direction, ray_in.time),
+            pdf: 0.0,
+            is_specular: true,
+        })
+    }
+
+    fn albedo(&self, _rec: &HitRecord) -> Vec3 {
+        Vec3::new(1.0, 1.0, 1.0)
     }
 }
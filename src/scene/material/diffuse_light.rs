@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use crate::math::vec3::Vec3;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::scene::hittable::HitRecord;
+use crate::scene::material::{Material, ScatterRecord};
+use crate::scene::texture::Texture;
+use crate::scene::texture::solid::SolidColor;
+
+/// An emitter that radiates its texture's colour and scatters nothing.
+pub struct DiffuseLight {
+    pub tex: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn from_color(emit: Vec3) -> Self {
+        Self {
+            tex: Arc::new(SolidColor::new(emit)),
+        }
+    }
+    pub fn from_texture(tex: Arc<dyn Texture>) -> Self {
+        Self { tex }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn sample(
+        &self,
+        _ray_in: &Ray,
+        _rec: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, u: f64, v: f64, point: &Vec3) -> Vec3 {
+        self.tex.value(u, v, point)
+    }
+
+    fn albedo(&self, rec: &HitRecord) -> Vec3 {
+        self.tex.value(rec.u, rec.v, &rec.point)
+    }
+}
@@ -0,0 +1,58 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use crate::math::vec3::Vec3;
+use crate::ray::Ray;
+use crate::sampler::{Sampler, sample_uniform_sphere};
+use crate::scene::hittable::HitRecord;
+use crate::scene::material::{Material, ScatterRecord};
+use crate::scene::texture::Texture;
+use crate::scene::texture::solid::SolidColor;
+
+/// Phase function of a participating medium: scatters uniformly in all directions.
+pub struct Isotropic {
+    pub tex: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn from_color(albedo: Vec3) -> Self {
+        Self {
+            tex: Arc::new(SolidColor::new(albedo)),
+        }
+    }
+    pub fn from_texture(tex: Arc<dyn Texture>) -> Self {
+        Self { tex }
+    }
+}
+
+impl Material for Isotropic {
+    fn sample(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
+        Some(ScatterRecord {
+            attenuation: self.tex.value(rec.u, rec.v, &rec.point),
+            ray: Ray::new_with_time(
+                rec.point,
+                sample_uniform_sphere(sampler.get_2d()),
+                ray_in.time,
+            ),
+            pdf: 1.0 / (4.0 * PI),
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, _ray_in: &Ray, rec: &HitRecord, _direction: Vec3) -> Vec3 {
+        self.tex.value(rec.u, rec.v, &rec.point) / (4.0 * PI)
+    }
+
+    fn pdf(&self, _ray_in: &Ray, _rec: &HitRecord, _direction: Vec3) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn albedo(&self, rec: &HitRecord) -> Vec3 {
+        self.tex.value(rec.u, rec.v, &rec.point)
+    }
+}
@@ -1,9 +1,11 @@
+use std::f64::consts::PI;
 use std::sync::Arc;
 
 use crate::math::vec3::Vec3;
 use crate::ray::Ray;
+use crate::sampler::{Sampler, sample_uniform_sphere};
 use crate::scene::hittable::HitRecord;
-use crate::scene::material::Material;
+use crate::scene::material::{Material, ScatterRecord};
 use crate::scene::texture::Texture;
 use crate::scene::texture::solid::SolidColor;
 
@@ -23,14 +25,34 @@ impl Lambertian {
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Vec3, Ray)> {
-        let mut scatter_direction = rec.normal + Vec3::random_unit_vector();
+    /// Cosine-weighted: a unit vector offset by the normal.
+    fn sample(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
+        let mut scatter_direction = rec.normal + sample_uniform_sphere(sampler.get_2d());
         if scatter_direction.is_near_zero() {
             scatter_direction = rec.normal;
         }
-        Some((
-            self.tex.value(rec.u, rec.v, &rec.point),
-            Ray::new_with_time(rec.point, scatter_direction, ray_in.time),
-        ))
+        Some(ScatterRecord {
+            attenuation: self.tex.value(rec.u, rec.v, &rec.point),
+            ray: Ray::new_with_time(rec.point, scatter_direction, ray_in.time),
+            pdf: self.pdf(ray_in, rec, scatter_direction),
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, ray_in: &Ray, rec: &HitRecord, direction: Vec3) -> Vec3 {
+        self.tex.value(rec.u, rec.v, &rec.point) * self.pdf(ray_in, rec, direction)
+    }
+
+    fn pdf(&self, _ray_in: &Ray, rec: &HitRecord, direction: Vec3) -> f64 {
+        rec.normal.dot(direction.normalized()).max(0.0) / PI
+    }
+
+    fn albedo(&self, rec: &HitRecord) -> Vec3 {
+        self.tex.value(rec.u, rec.v, &rec.point)
     }
 }
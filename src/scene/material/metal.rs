@@ -1,7 +1,13 @@
+use std::f64::consts::PI;
+
 use crate::{
     math::vec3::Vec3,
     ray::Ray,
-    scene::{hittable::HitRecord, material::Material},
+    sampler::{Sampler, sample_uniform_sphere},
+    scene::{
+        hittable::HitRecord,
+        material::{Material, ScatterRecord},
+    },
 };
 pub struct Metal {
     pub albedo: Vec3,
@@ -18,20 +24,63 @@ impl Metal {
 }
 
 impl Material for Metal {
-    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Vec3, Ray)> {
+    fn sample(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
         let reflected = r_in.direction.normalized().reflect(rec.normal);
 
         let scattered = Ray::new_with_time(
             rec.point,
-            reflected + Vec3::random_unit_vector() * self.fuzz,
+            reflected + sample_uniform_sphere(sampler.get_2d()) * self.fuzz,
             r_in.time,
         );
 
         // only reflect if we’re not below the surface
-        if scattered.direction.dot(rec.normal) > 0.0 {
-            Some((self.albedo, scattered))
-        } else {
-            None
+        if scattered.direction.dot(rec.normal) <= 0.0 {
+            return None;
+        }
+        Some(ScatterRecord {
+            attenuation: self.albedo,
+            ray: scattered,
+            pdf: self.pdf(r_in, rec, scattered.direction),
+            is_specular: self.fuzz == 0.0,
+        })
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Vec3 {
+        if direction.dot(rec.normal) <= 0.0 {
+            return Vec3::default();
         }
+        self.albedo * self.pdf(r_in, rec, direction)
+    }
+
+    /// Density of `reflected + fuzz * (uniform point on the unit sphere)`
+    /// pointing along `direction`: the sphere's area density converted to
+    /// solid angle at each place the direction crosses it.
+    fn pdf(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> f64 {
+        if self.fuzz == 0.0 {
+            return 0.0;
+        }
+        let reflected = r_in.direction.normalized().reflect(rec.normal);
+        let b = direction.normalized().dot(reflected);
+        let c = 1.0 - self.fuzz * self.fuzz;
+        let discriminant = b * b - c;
+        if discriminant <= 0.0 {
+            return 0.0;
+        }
+        let root = discriminant.sqrt();
+        let t_squared: f64 = [b - root, b + root]
+            .into_iter()
+            .filter(|&t| t > 0.0)
+            .map(|t| t * t)
+            .sum();
+        t_squared / (4.0 * PI * self.fuzz * root)
+    }
+
+    fn albedo(&self, _rec: &HitRecord) -> Vec3 {
+        self.albedo
     }
 }
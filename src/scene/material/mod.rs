@@ -1,11 +1,57 @@
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
 
 use crate::math::vec3::Vec3;
 use crate::ray::Ray;
+use crate::sampler::Sampler;
 use crate::scene::hittable::HitRecord;
 
+/// A direction chosen by `Material::sample`.
+#[derive(Clone, Copy, Debug)]
+pub struct ScatterRecord {
+    /// Throughput of the sampled direction: `eval / pdf`, or the reflectance
+    /// of a specular lobe.
+    pub attenuation: Vec3,
+    pub ray: Ray,
+    /// Solid-angle density of `ray.direction`; meaningless when `is_specular`.
+    pub pdf: f64,
+    /// The direction comes from a delta distribution (a perfect mirror or
+    /// glass), which `eval` and `pdf` cannot represent.
+    pub is_specular: bool,
+}
+
 pub trait Material: Sync + Send {
-    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Vec3, Ray)>;
+    /// Chooses an outgoing direction for light leaving along
+    /// `-ray_in.direction`, or `None` if the ray is absorbed.
+    fn sample(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord>;
+
+    /// Scattering function times the cosine term for light arriving from
+    /// `direction`. Zero for specular lobes, which can't be evaluated.
+    fn eval(&self, _ray_in: &Ray, _rec: &HitRecord, _direction: Vec3) -> Vec3 {
+        Vec3::default()
+    }
+
+    /// Solid-angle density with which `sample` picks `direction`.
+    fn pdf(&self, _ray_in: &Ray, _rec: &HitRecord, _direction: Vec3) -> f64 {
+        0.0
+    }
+
+    /// Overall surface colour at the hit, for diagnostics and denoising
+    /// guides: the reflectance a white light would show.
+    fn albedo(&self, _rec: &HitRecord) -> Vec3 {
+        Vec3::default()
+    }
+
+    /// Radiance emitted from the surface; black for everything but lights.
+    fn emitted(&self, _u: f64, _v: f64, _point: &Vec3) -> Vec3 {
+        Vec3::default()
+    }
 }
@@ -1,6 +1,8 @@
 pub mod aabb;
+pub mod background;
 pub mod bvh;
 pub mod hittable;
 pub mod hittable_list;
 pub mod material;
 pub mod texture;
+pub mod world;
@@ -12,9 +12,13 @@ pub struct ImageTexture {
 
 impl ImageTexture {
     pub fn new<P: AsRef<Path>>(path: P) -> Self {
-        let image = image::open(path).expect("failed to load image").to_rgb32f();
+        Self::load(path).expect("failed to load image")
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_rgb32f();
         let (w, h) = image.dimensions();
-        Self {
+        Ok(Self {
             width: w,
             height: h,
             pixels: image
@@ -27,13 +31,13 @@ impl ImageTexture {
                     )
                 })
                 .collect(),
-        }
+        })
     }
 }
 
 impl Texture for ImageTexture {
     fn value(&self, mut u: f64, mut v: f64, _: &Vec3) -> Vec3 {
-        if self.height <= 0 {
+        if self.height == 0 {
             return Vec3::new(0.0, 1.0, 1.0);
         }
         u = u.clamp(0.0, 1.0);
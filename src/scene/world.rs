@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use crate::math::interval::Interval;
+use crate::ray::Ray;
+use crate::scene::background::Background;
+use crate::scene::background::gradient::GradientBackground;
+use crate::scene::hittable::{HitRecord, Hittable};
+
+/// Everything the camera renders: the objects, a registry of the emitters
+/// that should be sampled directly for lighting, and the background.
+pub struct World {
+    pub objects: Arc<dyn Hittable>,
+    pub lights: Vec<Arc<dyn Hittable>>,
+    /// Radiance seen by rays that escape the scene.
+    pub background: Arc<dyn Background>,
+}
+
+impl World {
+    pub fn new(objects: Arc<dyn Hittable>) -> Self {
+        Self {
+            objects,
+            lights: vec![],
+            background: Arc::new(GradientBackground::sky()),
+        }
+    }
+
+    /// Registers an emissive object for light sampling. The object must
+    /// also be part of `objects` to be visible and cast shadows.
+    pub fn add_light(&mut self, light: Arc<dyn Hittable>) {
+        self.lights.push(light);
+    }
+
+    pub fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        self.objects.hit(ray, ray_t)
+    }
+
+    /// Density with which light sampling from `ray.origin` would have chosen
+    /// the point the ray hits at `t`, including the uniform light choice.
+    /// Only lights that the ray actually reaches at `t` contribute.
+    pub fn light_pdf(&self, ray: &Ray, t: f64) -> f64 {
+        if self.lights.is_empty() {
+            return 0.0;
+        }
+        let tolerance = 1e-4 * (1.0 + t * ray.direction.length());
+        let window = Interval::new(t - tolerance, t + tolerance);
+        let total: f64 = self
+            .lights
+            .iter()
+            .filter(|light| light.hit(ray, window).is_some())
+            .map(|light| light.pdf_value(ray.origin, ray.direction, ray.time))
+            .sum();
+        total / self.lights.len() as f64
+    }
+}